@@ -3,10 +3,7 @@ use std::{
     sync::{LazyLock, Mutex, RwLock},
 };
 use yggdrasil_error::{Validate, Validation, YggdrasilError};
-use yggdrasil_shared::{
-    optimize::{CodeOptimizer, InsertIgnore, RefineRules, RemarkTags},
-    parse_grammar, parse_grammar_raw, GrammarInfo,
-};
+use yggdrasil_shared::{optimize_grammar, parse_grammar, parse_grammar_raw, GrammarInfo};
 
 static CACHE_MANAGER: LazyLock<Mutex<CacheManager>> = LazyLock::new(|| Mutex::new(CacheManager::default()));
 
@@ -42,11 +39,8 @@ impl GaiaSystem {
     }
     pub fn get_optimized(&self, language: &str) -> Validation<GrammarInfo> {
         let mut errors = vec![];
-        let mut info = self.get_grammar(language).validate(&mut errors)?;
-        info = RefineRules::default().optimize(&info).validate(&mut errors)?;
-        info = InsertIgnore::default().optimize(&info).validate(&mut errors)?;
-        info = RemarkTags::default().optimize(&info).validate(&mut errors)?;
-        Validation::Success { value: info, diagnostics: errors }
+        let info = self.get_grammar(language).validate(&mut errors)?;
+        optimize_grammar(info, errors)
     }
 }
 
@@ -1,9 +1,9 @@
 use super::*;
 use crate::{cache::GrammarCache, GaiaSystem};
-use std::{collections::BTreeMap, env::current_dir, fs::read_to_string};
+use std::{collections::BTreeMap, env::current_dir};
 use wax::{CandidatePath, Glob, LinkBehavior, Pattern, WalkEntry, WalkError};
 use yggdrasil_error::{Failure, Success, Validation};
-use yggdrasil_shared::{parse_grammar, parse_grammar_raw, GrammarInfo};
+use yggdrasil_shared::{parse_grammar, GrammarInfo, GrammarLinker};
 
 /// Create a new grammar project
 #[derive(Parser)]
@@ -19,7 +19,7 @@ impl CommandBuild {
         for entry in glob.walk_with_behavior(current_dir()?, LinkBehavior::ReadTarget).not([".test"])? {
             match self.insert_item(entry) {
                 Ok(_) => {}
-                Err(_) => {}
+                Err(e) => eprintln!("{e}"),
             }
         }
         Ok(())
@@ -28,8 +28,16 @@ impl CommandBuild {
         let item = item?;
         let cache = GaiaSystem::default();
         if item.path().is_file() {
-            let text = read_to_string(item.path())?;
-            let grammar = parse_grammar_raw(&text)?;
+            let grammar = match GrammarLinker::default().link_file(item.path()) {
+                Success { value, diagnostics } => {
+                    diagnostics.iter().for_each(|e| eprintln!("{e}"));
+                    value
+                }
+                Failure { fatal, diagnostics } => {
+                    diagnostics.iter().for_each(|e| eprintln!("{e}"));
+                    return Err(fatal);
+                }
+            };
         }
         Ok(())
     }
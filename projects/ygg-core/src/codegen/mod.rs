@@ -3,10 +3,16 @@ mod target_pest;
 
 pub(crate) mod target_railroad;
 #[allow(unused)]
-mod target_rust;
+pub(crate) mod target_rust;
+#[cfg(feature = "tree-sitter")]
+pub(crate) mod target_tree_sitter;
+pub(crate) mod target_vscode;
 
 pub use self::target_railroad::Railroad;
 #[cfg(feature = "tree-sitter")]
-pub(crate) mod target_vscode;
+pub use self::target_tree_sitter::TreeSitterCodegen;
 
-pub use self::target_rust::{RustCodegen, RustModule};
+pub use self::{
+    target_rust::{RustCodegen, RustModule},
+    target_vscode::{VscodeCodegen, VscodeExtension},
+};
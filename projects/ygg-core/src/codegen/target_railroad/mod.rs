@@ -61,11 +61,10 @@ impl AsRailroad for GrammarRule {
             GrammarBody::Class { term } => {
                 s.push(term.as_railroad(config));
             }
-            GrammarBody::Union { branches } => {
-                let concat = ChoiceExpression { branches: branches.iter().map(|v| v.branch.clone()).collect() };
+            GrammarBody::Union { branches: variants } | GrammarBody::Climb { priority: variants } => {
+                let concat = ChoiceExpression { branches: variants.iter().map(|v| v.branch.clone()).collect() };
                 s.push(concat.as_railroad(config));
             }
-            GrammarBody::Climb { .. } => {}
         }
         s.push(Box::new(SimpleEnd));
         return Box::new(s);
@@ -87,6 +86,7 @@ impl AsRailroad for ExpressionBody {
             ExpressionBody::Rule(e) => e.as_railroad(config),
             ExpressionBody::Call(e) => Box::new(Terminal::new(e.name.to_string(), &vec!["function"])),
             ExpressionBody::Ignored => Box::new(Terminal::new("IGNORED".to_string(), &vec!["character"])),
+            ExpressionBody::Cut => Box::new(Terminal::new("CUT".to_string(), &vec!["character"])),
             ExpressionBody::Text(v) => Box::new(Terminal::new(v.text.to_string(), &vec!["string"])),
             ExpressionBody::CharacterAny => Box::new(Terminal::new("ANY".to_string(), &vec!["character"])),
             ExpressionBody::CharacterRestOfLine => Box::new(Terminal::new("RestOfLine".to_string(), &vec!["character"])),
@@ -131,6 +131,7 @@ impl AsRailroad for UnaryExpression {
                 YggdrasilOperator::Recursive => continue,
                 YggdrasilOperator::Negative => continue,
                 YggdrasilOperator::Positive => continue,
+                YggdrasilOperator::Label(_) | YggdrasilOperator::Error(_) => continue,
             }
         }
         return base;
@@ -1,5 +1,11 @@
 use super::*;
 use crate::codegen::target_rust::filters::safe_rust_id;
+use std::collections::BTreeMap;
+use yggdrasil_ir::{
+    data::YggdrasilRegex,
+    nodes::{ExpressionBody, YggdrasilExpression},
+    rule::GrammarBody,
+};
 
 pub trait GrammarExt {
     fn rule_name(&self) -> String;
@@ -7,6 +13,7 @@ pub trait GrammarExt {
     fn ignore_rules_empty(&self) -> bool;
     fn ignore_rule_pattern(&self) -> String;
     fn ignore_rule_match(&self) -> String;
+    fn regex_constants(&self) -> String;
 }
 
 impl GrammarExt for GrammarInfo {
@@ -20,7 +27,7 @@ impl GrammarExt for GrammarInfo {
         false
     }
     fn ignore_rule_pattern(&self) -> String {
-        let mut out = String::from("Self::IgnoreText | Self::IgnoreRegex");
+        let mut out = String::from("Self::IgnoreText | Self::IgnoreRegex | Self::IgnoreError");
         for ignore in self.ignore_rules() {
             out.push_str(&format!(" | Self::{}", safe_rust_id(&ignore.name.text).unwrap()))
         }
@@ -40,4 +47,40 @@ impl GrammarExt for GrammarInfo {
         }
         out
     }
+    /// The regexes are compiled to DFAs here, so that the generated parser doesn't compile them at runtime
+    fn regex_constants(&self) -> String {
+        let mut regexes = BTreeMap::new();
+        for rule in self.rules.values() {
+            match &rule.body {
+                GrammarBody::Class { term } => find_regex(term, &mut regexes),
+                GrammarBody::Union { branches } | GrammarBody::Climb { priority: branches } => {
+                    branches.iter().for_each(|v| find_regex(&v.branch, &mut regexes))
+                }
+            }
+            rule.recover.iter().for_each(|e| find_regex(e, &mut regexes));
+        }
+        let mut out = String::new();
+        for (name, regex) in regexes {
+            match regex.build() {
+                Ok(o) => writeln!(out, "{o}").unwrap(),
+                Err(e) => {
+                    let message = format!("regex `{}` can't be compiled to `{name}`: {e}", regex.raw);
+                    writeln!(out, "compile_error!({message:?});").unwrap()
+                }
+            }
+        }
+        out
+    }
+}
+
+fn find_regex<'i>(info: &'i YggdrasilExpression, out: &mut BTreeMap<String, &'i YggdrasilRegex>) {
+    match &info.body {
+        ExpressionBody::Choice(node) => node.branches.iter().for_each(|e| find_regex(e, out)),
+        ExpressionBody::Concat(node) => node.sequence.iter().for_each(|e| find_regex(e, out)),
+        ExpressionBody::Unary(node) => find_regex(&node.base, out),
+        ExpressionBody::Regex(regex) => {
+            out.entry(regex.constant_name()).or_insert(regex);
+        }
+        _ => {}
+    }
 }
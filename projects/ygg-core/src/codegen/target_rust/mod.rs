@@ -29,6 +29,7 @@ mod grammar_ext;
 mod rule_ext;
 
 use self::{grammar_ext::GrammarExt, rule_ext::RuleExt};
+pub(crate) use self::rule_ext::literal_set;
 
 #[derive(Clone, Debug)]
 pub struct RustCodegen {
@@ -2,13 +2,20 @@ use convert_case::{Case, Casing};
 use std::fmt::Debug;
 use yggdrasil_ir::{
     nodes::{ExpressionBody, YggdrasilExpression, YggdrasilOperator},
-    rule::{GrammarBody, YggdrasilIdentifier},
+    rule::{ClimbOperator, ClimbPosition, GrammarBody, YggdrasilIdentifier},
 };
 
 use super::*;
+use crate::codegen::target_rust::filters::safe_rust_id;
 
 pub(super) trait RuleExt {
     fn parser_expression(&self) -> String;
+    fn rule_method(&self) -> &'static str;
+    fn recover_expression(&self) -> String;
+    fn report_start(&self) -> String;
+    fn report_end(&self) -> String;
+    fn climb_name(&self) -> String;
+    fn climb_expression(&self, grammar: &GrammarInfo) -> String;
 }
 
 impl RuleExt for GrammarRule {
@@ -22,18 +29,135 @@ impl RuleExt for GrammarRule {
                 }
             }
             GrammarBody::Union { branches } => {
+                // the branches are skipped after a final failure, see `State::cut`
                 w.push_str("Err(s)");
                 for variant in branches {
-                    w.push_str(".or_else(|s|");
+                    w.push_str(".or_else(|s|s.alternative(|s|");
                     variant.branch.write(&mut w, self, false).unwrap();
-                    w.push_str(")");
+                    w.push_str("))");
                 }
             }
-            GrammarBody::Climb { .. } => w.push_str("Err(/* Climb node */s)"),
+            GrammarBody::Climb { .. } => write!(w, "{}(s, 0)", self.climb_name()).unwrap(),
             // GrammarBody::TokenSet { .. } => unreachable!("TokenSet is not an expression"),
         }
         w
     }
+    fn rule_method(&self) -> &'static str {
+        if self.left_recursive {
+            "rule_left"
+        }
+        else if self.memo {
+            "rule_memo"
+        }
+        else {
+            "rule"
+        }
+    }
+    /// Rules are only looked ahead, the next statement shouldn't be swallowed by the error
+    fn recover_expression(&self) -> String {
+        let mut w = String::from("Err(s)");
+        for sync in &self.recover {
+            w.push_str(".or_else(|s|");
+            let result = match sync.is_rule() {
+                true => {
+                    w.push_str("s.lookahead(true, |s|");
+                    sync.write(&mut w, self, false).map(|_| w.push_str(")"))
+                }
+                false => sync.write(&mut w, self, true),
+            };
+            if let Err(e) = result {
+                write!(w, "Err(/*{e}*/s)").unwrap()
+            }
+            w.push_str(")");
+        }
+        w
+    }
+    /// `@label` and `@error` wrap the whole rule, `state` is shadowed by the closures
+    fn report_start(&self) -> String {
+        let mut w = String::new();
+        if let Some(label) = &self.label {
+            write!(w, "state.label({:?}, |state|", label).unwrap()
+        }
+        if let Some(message) = &self.error {
+            write!(w, "state.error({:?}, |state|", message).unwrap()
+        }
+        w
+    }
+    fn report_end(&self) -> String {
+        ")".repeat(self.label.iter().count() + self.error.iter().count())
+    }
+    fn climb_name(&self) -> String {
+        format!("climb_{}", self.name.text).to_case(Case::Snake)
+    }
+    /// Precedence climbing, the first branch binds tightest
+    ///
+    /// - atoms and prefix operators are tried first, each produces a node tagged with the branch name
+    /// - then postfix and infix operators whose priority is not less than `min` wrap the left operand
+    fn climb_expression(&self, grammar: &GrammarInfo) -> String {
+        let rule = format!("{}::{}", grammar.rule_name(), safe_rust_id(&self.name.text).unwrap());
+        let operators = self.climb_operators();
+        let mut w = String::new();
+        w.push_str("let start = state.token_index();");
+        w.push_str("let mut state = Err(state)");
+        for o in operators.iter().filter(|o| matches!(o.position, ClimbPosition::Atomic | ClimbPosition::Prefix)) {
+            write!(w, ".or_else(|s| s.alternative(|s| s.rule({rule}, |s|").unwrap();
+            if let Err(e) = o.write_operator(&mut w, self, &rule) {
+                write!(w, "Err(/*{e}*/s)").unwrap()
+            }
+            write!(w, ").and_then(|s| s.tag_node({:?}))))", o.variant_name()).unwrap();
+        }
+        w.push_str("?;");
+        let leds =
+            operators.iter().filter(|o| matches!(o.position, ClimbPosition::Postfix | ClimbPosition::Infix)).collect_vec();
+        if leds.is_empty() {
+            w.push_str("Ok(state)");
+            return w;
+        }
+        w.push_str("loop { state = match Err(state)");
+        for o in leds {
+            let lhs = o.lhs.and_then(|e| e.tag.as_ref()).map(|t| t.text.to_case(Case::Snake)).unwrap_or("lhs".to_string());
+            let head = format!("if {} >= min {{ s.climb(start, {rule}, {:?}, {:?}, |s|", o.priority, lhs, o.variant_name());
+            write!(w, ".or_else(|s| s.alternative(|s| {head}").unwrap();
+            if let Err(e) = o.write_operator(&mut w, self, &rule) {
+                write!(w, "Err(/*{e}*/s)").unwrap()
+            }
+            w.push_str(") } else { Err(s) }))");
+        }
+        // stopping here is the last alternative
+        w.push_str("{ Ok(s) => s, Err(s) => return s.alternative(Ok) } }");
+        w
+    }
+}
+
+trait ClimbExt {
+    fn variant_name(&self) -> String;
+    fn write_operator(&self, w: &mut String, ctx: &GrammarRule, rule: &str) -> std::fmt::Result;
+}
+
+impl<'i> ClimbExt for ClimbOperator<'i> {
+    fn variant_name(&self) -> String {
+        match &self.variant.tag {
+            Some(s) => s.text.to_case(Case::Snake),
+            None => unreachable!("have you run remark?"),
+        }
+    }
+    /// Write the operator and the right operand, the left operand is already parsed
+    fn write_operator(&self, w: &mut String, ctx: &GrammarRule, rule: &str) -> std::fmt::Result {
+        w.push_str("s.sequence(|s| Ok(s)");
+        for item in self.operator {
+            w.push_str(".and_then(|s|");
+            item.write(w, ctx, false)?;
+            w.push_str(")");
+        }
+        if let Some(rhs) = self.rhs {
+            write!(w, ".and_then(|s| s.rule({rule}, |s| {}(s, {})))", ctx.climb_name(), self.rhs_priority())?;
+            if let Some(s) = &rhs.tag {
+                write!(w, ".and_then(|s| s.tag_node({:?}))", s.text.to_case(Case::Snake))?
+            }
+        }
+        w.push_str(")");
+        Ok(())
+    }
 }
 
 trait NodeExt {
@@ -43,14 +167,28 @@ trait NodeExt {
 impl NodeExt for YggdrasilExpression {
     fn write(&self, w: &mut String, ctx: &GrammarRule, root: bool) -> std::fmt::Result {
         match &self.body {
-            ExpressionBody::Choice(v) => {
-                w.push_str("Err(s)");
-                for pat in &v.branches {
-                    w.push_str(".or_else(|s|");
-                    pat.write(w, ctx, false)?;
-                    w.push_str(")");
+            ExpressionBody::Choice(v) => match literal_set(&v.branches) {
+                Some((texts, insensitive)) => {
+                    match root {
+                        true => w.push_str("s.match_trie("),
+                        false => w.push_str("builtin_trie(s,"),
+                    }
+                    w.push_str("{static TRIE:OnceLock<TextTrie>=OnceLock::new();TRIE.get_or_init(||TextTrie::new(&[");
+                    for text in texts {
+                        write!(w, "{:?},", text)?;
+                    }
+                    write!(w, "],{})", insensitive)?;
+                    w.push_str(")})");
                 }
-            }
+                None => {
+                    w.push_str("Err(s)");
+                    for pat in &v.branches {
+                        w.push_str(".or_else(|s|s.alternative(|s|");
+                        pat.write(w, ctx, false)?;
+                        w.push_str("))");
+                    }
+                }
+            },
             ExpressionBody::Concat(v) => {
                 w.push_str("s.sequence(|s|");
                 w.push_str("Ok(s)");
@@ -74,12 +212,10 @@ impl NodeExt for YggdrasilExpression {
                                 write!(w, "s.repeat({}..{},|s|", min, max)?
                             }
                         }
-                        YggdrasilOperator::Boxing => {
-                            todo!()
-                        }
-                        YggdrasilOperator::Recursive => {
-                            todo!()
-                        }
+                        // only the ast is boxed, the input is matched as a group
+                        YggdrasilOperator::Boxing | YggdrasilOperator::Recursive => w.push_str("s.sequence(|s|"),
+                        YggdrasilOperator::Label(label) => write!(w, "s.label({:?},|s|", label)?,
+                        YggdrasilOperator::Error(message) => write!(w, "s.error({:?},|s|", message)?,
                     }
                 }
                 v.base.write(w, ctx, false)?;
@@ -88,32 +224,30 @@ impl NodeExt for YggdrasilExpression {
                 }
             }
             ExpressionBody::Ignored => w.push_str("builtin_ignore(s)"),
-            ExpressionBody::Call(v) => write!(w, "Err(/*{}*/s)", v.name.to_string())?,
+            ExpressionBody::Cut => w.push_str("s.cut()"),
+            // fails the build of the generated parser instead of a parser that never matches
+            ExpressionBody::Call(v) => write!(w, "compile_error!(\"macro `{}` is not supported\")", v.name.to_string())?,
             ExpressionBody::Rule(r) => {
                 let name = format!("parse_{}", r.name.text).to_case(Case::Snake);
                 write!(w, "{name}(s)")?
             }
             ExpressionBody::Text(v) if root => write!(w, "s.match_string({:?}, {})", v.text, v.insensitive)?,
             ExpressionBody::Text(v) => write!(w, "builtin_text(s,{:?},{})", v.text, v.insensitive)?,
-            ExpressionBody::Regex(r) if root => {
-                w.push_str("s.match_regex({static REGEX:OnceLock<Regex>=OnceLock::new();REGEX.get_or_init(||Regex::new(");
-                write!(w, "{}", r)?;
-                w.push_str(").unwrap())})");
-            }
-            ExpressionBody::Regex(r) => {
-                w.push_str("builtin_regex(s,{static REGEX:OnceLock<Regex>=OnceLock::new();REGEX.get_or_init(||Regex::new(");
-                write!(w, "{}", r)?;
-                w.push_str(").unwrap())})");
-            }
+            // the constants are written by `GrammarExt::regex_constants`
+            ExpressionBody::Regex(r) if root => write!(w, "s.match_compiled(&{})", r.constant_name())?,
+            ExpressionBody::Regex(r) => write!(w, "builtin_regex(s,&{})", r.constant_name())?,
             ExpressionBody::CharacterAny if root => w.push_str("s.match_char_if(|_|true)"),
             ExpressionBody::CharacterAny => w.push_str("builtin_any(s)"),
-            ExpressionBody::CharacterRestOfLine => {}
-            ExpressionBody::CharacterRange(_) if root => {}
-            ExpressionBody::CharacterRange(_) => {}
-            ExpressionBody::Boolean(_) if root => {}
-            ExpressionBody::Boolean(_) => {}
-            ExpressionBody::Integer(_) if root => {}
-            ExpressionBody::Integer(_) => {}
+            // the line break is not consumed
+            ExpressionBody::CharacterRestOfLine if root => w.push_str("s.skip_until(&[\"\\n\", \"\\r\"])"),
+            ExpressionBody::CharacterRestOfLine => w.push_str("builtin_rest_of_line(s)"),
+            // `match_range` treats the range as inclusive
+            ExpressionBody::CharacterRange(v) if root => write!(w, "s.match_range({:?}..{:?})", v.start(), v.end())?,
+            ExpressionBody::CharacterRange(v) => write!(w, "builtin_range(s,{:?}..{:?})", v.start(), v.end())?,
+            ExpressionBody::Boolean(true) => w.push_str("Ok(s)"),
+            ExpressionBody::Boolean(false) => w.push_str("Err(s)"),
+            ExpressionBody::Integer(v) if root => write!(w, "s.match_string({:?}, false)", v.to_string())?,
+            ExpressionBody::Integer(v) => write!(w, "builtin_text(s,{:?},false)", v.to_string())?,
         }
         match &self.tag {
             Some(s) => write!(w, ".and_then(|s| s.tag_node({:?}))", s.text.to_case(Case::Snake))?,
@@ -122,3 +256,21 @@ impl NodeExt for YggdrasilExpression {
         Ok(())
     }
 }
+
+/// Choices made only of untagged texts with the same case are matched by a trie, see `FusionRules`
+pub(crate) fn literal_set(branches: &[YggdrasilExpression]) -> Option<(Vec<&str>, bool)> {
+    let mut texts = vec![];
+    let mut case = None;
+    for branch in branches {
+        match &branch.body {
+            ExpressionBody::Text(v) if branch.tag.is_none() && *case.get_or_insert(v.insensitive) == v.insensitive => {
+                texts.push(v.text.as_str())
+            }
+            _ => return None,
+        }
+    }
+    match texts.len() {
+        0 | 1 => None,
+        _ => Some((texts, case.unwrap_or_default())),
+    }
+}
@@ -0,0 +1,243 @@
+use askama::Template;
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use std::ops::Range;
+use yggdrasil_error::{Success, Validate, Validation, YggdrasilError};
+use yggdrasil_ir::{
+    grammar::GrammarInfo,
+    nodes::{ExpressionBody, YggdrasilExpression, YggdrasilOperator},
+    rule::{ClimbPosition, GrammarBody, GrammarRule},
+    traits::CodeGenerator,
+};
+
+/// Translates the grammar into the `grammar.js` of tree-sitter
+///
+/// Constructs that tree-sitter can't express, such as lookahead and macros, are reported and replaced by `blank()`.
+#[derive(Clone, Debug, Default)]
+pub struct TreeSitterCodegen {}
+
+#[derive(Template)]
+#[template(path = "tree-sitter/grammar.jinja", escape = "none")]
+pub struct TreeSitterWriteGrammar<'i> {
+    grammar: &'i GrammarInfo,
+    /// The entry rule comes first, tree-sitter starts from the first rule
+    rules: Vec<(String, String)>,
+    extras: Vec<String>,
+}
+
+impl CodeGenerator for TreeSitterCodegen {
+    type Output = String;
+
+    fn generate(&mut self, info: &GrammarInfo) -> Validation<Self::Output> {
+        let mut errors = vec![];
+        let rules = info.rules.values().sorted_by_key(|r| !r.entry);
+        let rules = rules.map(|r| (rule_name(&r.name.text), rule_body(r, &mut errors))).collect();
+        let extras = info.rules.values().filter(|r| r.ignored).map(|r| format!("$.{}", rule_name(&r.name.text))).collect();
+        let out = TreeSitterWriteGrammar { grammar: info, rules, extras }.render().recover(&mut errors)?;
+        Success { value: out, diagnostics: errors }
+    }
+}
+
+impl<'i> TreeSitterWriteGrammar<'i> {
+    fn grammar_name(&self) -> String {
+        rule_name(&self.grammar.name.text)
+    }
+}
+
+/// tree-sitter only accepts snake case names
+fn rule_name(name: &str) -> String {
+    name.to_case(Case::Snake)
+}
+
+trait AsTreeSitter {
+    /// `at` is the span of the closest expression that has one, the constants are reported there
+    fn as_tree_sitter(&self, rule: &GrammarRule, at: Option<Range<usize>>, errors: &mut Vec<YggdrasilError>) -> String;
+}
+
+fn rule_body(rule: &GrammarRule, errors: &mut Vec<YggdrasilError>) -> String {
+    match &rule.body {
+        GrammarBody::Class { term } => term.as_tree_sitter(rule, None, errors),
+        GrammarBody::Union { branches } => {
+            choice(branches.iter().map(|v| v.branch.as_tree_sitter(rule, None, errors)).collect())
+        }
+        // the first branch binds tighter, tree-sitter prefers the higher precedence
+        GrammarBody::Climb { .. } => {
+            let mut branches = vec![];
+            for o in rule.climb_operators() {
+                let branch = o.variant.branch.as_tree_sitter(rule, None, errors);
+                let branch = match o.position {
+                    ClimbPosition::Atomic => branch,
+                    ClimbPosition::Infix if o.variant.right_associative => format!("prec.right({}, {branch})", o.priority),
+                    ClimbPosition::Infix | ClimbPosition::Postfix => format!("prec.left({}, {branch})", o.priority),
+                    ClimbPosition::Prefix => format!("prec.right({}, {branch})", o.priority),
+                };
+                branches.push(branch)
+            }
+            choice(branches)
+        }
+    }
+}
+
+impl AsTreeSitter for YggdrasilExpression {
+    fn as_tree_sitter(&self, rule: &GrammarRule, at: Option<Range<usize>>, errors: &mut Vec<YggdrasilError>) -> String {
+        let body = self.body.as_tree_sitter(rule, self.get_range().or(at), errors);
+        match &self.tag {
+            Some(tag) if body != "blank()" => format!("field({}, {body})", js_string(&rule_name(&tag.text))),
+            _ => body,
+        }
+    }
+}
+
+impl AsTreeSitter for ExpressionBody {
+    fn as_tree_sitter(&self, rule: &GrammarRule, at: Option<Range<usize>>, errors: &mut Vec<YggdrasilError>) -> String {
+        match self {
+            ExpressionBody::Choice(v) => {
+                choice(v.branches.iter().map(|e| e.as_tree_sitter(rule, at.clone(), errors)).collect())
+            }
+            ExpressionBody::Concat(v) => {
+                // the ignored rules are in `extras`
+                let items = v.sequence.iter().map(|e| e.as_tree_sitter(rule, at.clone(), errors));
+                let items = items.filter(|s| s != "blank()").collect_vec();
+                match items.as_slice() {
+                    [] => "blank()".to_string(),
+                    [one] => one.clone(),
+                    _ => format!("seq({})", items.join(", ")),
+                }
+            }
+            ExpressionBody::Unary(v) => {
+                let mut w = v.base.as_tree_sitter(rule, at.clone(), errors);
+                for o in &v.operators {
+                    // `repeat(blank())` is not allowed, the lookahead is already reported
+                    if w == "blank()" {
+                        break;
+                    }
+                    w = match o {
+                        YggdrasilOperator::Positive | YggdrasilOperator::Negative => {
+                            unsupported(rule, at.clone(), "lookahead", errors);
+                            "blank()".to_string()
+                        }
+                        YggdrasilOperator::RepeatsBetween { min, max } => repeats(w, *min, *max),
+                        YggdrasilOperator::Boxing
+                        | YggdrasilOperator::Recursive
+                        | YggdrasilOperator::Label(_)
+                        | YggdrasilOperator::Error(_) => w,
+                    }
+                }
+                w
+            }
+            ExpressionBody::Rule(r) => format!("$.{}", rule_name(&r.name.text)),
+            ExpressionBody::Text(t) if t.insensitive => {
+                let mut w = String::from("/");
+                for c in t.text.chars() {
+                    match (c.to_lowercase().next(), c.to_uppercase().next()) {
+                        (Some(lower), Some(upper)) if lower != upper => w.push_str(&format!("[{lower}{upper}]")),
+                        _ => w.push_str(&regex_escape(&c.to_string())),
+                    }
+                }
+                w.push('/');
+                w
+            }
+            ExpressionBody::Text(t) => js_string(&t.text),
+            ExpressionBody::Ignored | ExpressionBody::Cut => "blank()".to_string(),
+            ExpressionBody::CharacterAny => "/[\\s\\S]/".to_string(),
+            ExpressionBody::CharacterRestOfLine => "/[^\\n]*/".to_string(),
+            ExpressionBody::CharacterRange(r) => {
+                format!("/[{}-{}]/", regex_escape(&r.start().to_string()), regex_escape(&r.end().to_string()))
+            }
+            ExpressionBody::Regex(r) => regex_literal(&r.raw),
+            ExpressionBody::Call(v) => {
+                unsupported(rule, Some(v.range.clone()), "macro call", errors);
+                "blank()".to_string()
+            }
+            ExpressionBody::Boolean(true) => "blank()".to_string(),
+            ExpressionBody::Integer(_) | ExpressionBody::Boolean(false) => {
+                unsupported(rule, at, "constant", errors);
+                "blank()".to_string()
+            }
+        }
+    }
+}
+
+/// Reported at the expression, or at the rule if no enclosing expression has a span
+fn unsupported(rule: &GrammarRule, range: Option<Range<usize>>, what: &str, errors: &mut Vec<YggdrasilError>) {
+    let message = format!("{what} in rule `{}` can't be expressed in tree-sitter, it's replaced by `blank()`", rule.name.text);
+    errors.push(YggdrasilError::syntax_error(message, range.unwrap_or(rule.range.clone())))
+}
+
+fn choice(branches: Vec<String>) -> String {
+    match branches.as_slice() {
+        [one] => one.clone(),
+        _ => format!("choice({})", branches.join(", ")),
+    }
+}
+
+/// tree-sitter only has `optional`, `repeat` and `repeat1`, other counts are unrolled
+fn repeats(item: String, min: u32, max: u32) -> String {
+    match (min, max) {
+        (0, 1) => format!("optional({item})"),
+        (0, u32::MAX) => format!("repeat({item})"),
+        (1, u32::MAX) => format!("repeat1({item})"),
+        (min, u32::MAX) => {
+            let mut items = vec![item.clone(); min as usize - 1];
+            items.push(format!("repeat1({item})"));
+            format!("seq({})", items.join(", "))
+        }
+        (min, max) => {
+            let mut items = vec![item.clone(); min as usize];
+            items.extend((min..max).map(|_| format!("optional({item})")));
+            match items.as_slice() {
+                [] => "blank()".to_string(),
+                [one] => one.clone(),
+                _ => format!("seq({})", items.join(", ")),
+            }
+        }
+    }
+}
+
+fn js_string(text: &str) -> String {
+    let mut w = String::from("'");
+    for c in text.chars() {
+        match c {
+            '\'' => w.push_str("\\'"),
+            '\\' => w.push_str("\\\\"),
+            '\n' => w.push_str("\\n"),
+            '\r' => w.push_str("\\r"),
+            '\t' => w.push_str("\\t"),
+            c => w.push(c),
+        }
+    }
+    w.push('\'');
+    w
+}
+
+/// The slashes are escaped unless they are already escaped
+fn regex_literal(raw: &str) -> String {
+    let mut w = String::from("/");
+    let mut escaped = false;
+    for c in raw.chars() {
+        if c == '/' && !escaped {
+            w.push('\\')
+        }
+        escaped = c == '\\' && !escaped;
+        w.push(c)
+    }
+    w.push('/');
+    w
+}
+
+fn regex_escape(text: &str) -> String {
+    let mut w = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '.' | '^' | '$' | '|' | '?' | '*' | '+' | '(' | ')' | '[' | ']' | '{' | '}' | '/' | '-' => {
+                w.push('\\');
+                w.push(c)
+            }
+            '\n' => w.push_str("\\n"),
+            '\r' => w.push_str("\\r"),
+            '\t' => w.push_str("\\t"),
+            c => w.push(c),
+        }
+    }
+    w
+}
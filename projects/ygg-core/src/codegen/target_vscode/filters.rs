@@ -0,0 +1,19 @@
+use std::fmt::Write;
+
+/// Quote the text as a json string
+pub fn json_string<S: AsRef<str>>(text: S) -> askama::Result<String> {
+    let mut w = String::from("\"");
+    for c in text.as_ref().chars() {
+        match c {
+            '"' => w.push_str("\\\""),
+            '\\' => w.push_str("\\\\"),
+            '\n' => w.push_str("\\n"),
+            '\r' => w.push_str("\\r"),
+            '\t' => w.push_str("\\t"),
+            c if c.is_control() => write!(w, "\\u{:04x}", c as u32)?,
+            c => w.push(c),
+        }
+    }
+    w.push('"');
+    Ok(w)
+}
@@ -1 +1,112 @@
+use askama::Template;
+use convert_case::{Case, Casing};
+use std::{
+    fs::{create_dir_all, File},
+    io::{Error, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+use yggdrasil_error::{Success, Validate, Validation};
+use yggdrasil_ir::{grammar::GrammarInfo, traits::CodeGenerator};
 
+mod filters;
+mod patterns;
+
+pub use self::patterns::TextMatePattern;
+
+/// Exports a TextMate grammar and the manifest of a VS Code extension that contributes it
+///
+/// The scopes are derived from `@style` annotations, token sets, text literals and regex rules.
+#[derive(Clone, Debug)]
+pub struct VscodeCodegen {
+    /// The publisher in the manifest
+    pub publisher: String,
+    /// The version of the extension
+    pub version: String,
+    /// The lowest version of VS Code supported by the extension
+    pub engine: String,
+}
+
+impl Default for VscodeCodegen {
+    fn default() -> Self {
+        Self { publisher: "ygg-lang".to_string(), version: "0.0.0".to_string(), engine: "^1.60.0".to_string() }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "vscode/language.jinja", escape = "none")]
+pub struct VscodeWriteLanguage<'i> {
+    grammar: &'i GrammarInfo,
+    patterns: Vec<TextMatePattern>,
+}
+
+#[derive(Template)]
+#[template(path = "vscode/package.jinja", escape = "none")]
+pub struct VscodeWritePackage<'i> {
+    grammar: &'i GrammarInfo,
+    config: VscodeCodegen,
+}
+
+#[derive(Default)]
+pub struct VscodeExtension {
+    /// The id of the language, such as `json5`
+    pub language: String,
+    /// `package.json`
+    pub package: String,
+    /// `syntaxes/<language>.tmLanguage.json`
+    pub grammar: String,
+}
+
+impl CodeGenerator for VscodeCodegen {
+    type Output = VscodeExtension;
+
+    fn generate(&mut self, info: &GrammarInfo) -> Validation<Self::Output> {
+        let mut errors = vec![];
+        let mut out = VscodeExtension { language: language_id(info), ..Default::default() };
+        let patterns = TextMatePattern::collect(info, &out.language, &mut errors);
+        out.grammar = VscodeWriteLanguage { grammar: info, patterns }.render().recover(&mut errors)?;
+        out.package = VscodeWritePackage { grammar: info, config: self.clone() }.render().recover(&mut errors)?;
+        Success { value: out, diagnostics: errors }
+    }
+}
+
+impl<'i> VscodeWriteLanguage<'i> {
+    fn scope_name(&self) -> String {
+        format!("source.{}", language_id(self.grammar))
+    }
+}
+
+impl<'i> VscodeWritePackage<'i> {
+    fn language_id(&self) -> String {
+        language_id(self.grammar)
+    }
+    /// `json5`, `.json5` and `*.json5` are all written as `.json5`
+    fn extensions(&self) -> Vec<String> {
+        self.grammar.extensions.iter().map(|s| format!(".{}", s.trim_start_matches('*').trim_start_matches('.'))).collect()
+    }
+}
+
+/// The lowercase name of the grammar, used as the language id and in the scopes
+fn language_id(info: &GrammarInfo) -> String {
+    info.name.text.to_case(Case::Kebab)
+}
+
+impl VscodeExtension {
+    /// Write the extension into the directory, the directory is created if it doesn't exist
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<PathBuf> {
+        let path = path.as_ref();
+        if path.exists() {
+            if !path.is_dir() {
+                return Err(Error::new(ErrorKind::InvalidInput, "Path is not a directory"));
+            }
+        }
+        else {
+            create_dir_all(path)?
+        }
+        let mut package = File::create(path.join("package.json"))?;
+        package.write_all(self.package.as_bytes())?;
+        create_dir_all(path.join("syntaxes"))?;
+        let mut grammar = File::create(path.join("syntaxes").join(format!("{}.tmLanguage.json", self.language)))?;
+        grammar.write_all(self.grammar.as_bytes())?;
+        path.canonicalize()
+    }
+}
@@ -0,0 +1,271 @@
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use yggdrasil_error::YggdrasilError;
+use yggdrasil_ir::{
+    grammar::GrammarInfo,
+    nodes::{ExpressionBody, YggdrasilExpression, YggdrasilOperator},
+    rule::{GrammarBody, GrammarRule},
+};
+
+/// A `match` pattern in the repository of the TextMate grammar
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TextMatePattern {
+    /// The key in the repository
+    pub key: String,
+    /// The scopes separated by spaces, such as `keyword.control.json5`
+    pub scope: String,
+    /// The oniguruma regex
+    pub regex: String,
+}
+
+impl TextMatePattern {
+    /// The patterns are tried in order: styled rules, token sets, the remaining literals, then regex rules
+    ///
+    /// Rules with a style that can't be matched by a regex are reported and not highlighted.
+    pub fn collect(info: &GrammarInfo, language: &str, errors: &mut Vec<YggdrasilError>) -> Vec<Self> {
+        let mut covered = BTreeSet::new();
+        let mut token_sets = vec![];
+        for (name, members) in &info.token_sets {
+            let mut regexes = vec![];
+            let mut styles = vec![];
+            for member in members.iter().filter_map(|id| info.rules.get(&id.text)) {
+                covered.insert(member.name.text.clone());
+                match rule_regex(member, info) {
+                    Some(s) => regexes.push(s),
+                    None => errors.push(unmatchable(member)),
+                }
+                if styles.is_empty() {
+                    styles = member.styles.clone();
+                }
+            }
+            if regexes.is_empty() {
+                continue;
+            }
+            let scope = match styles.is_empty() {
+                true => {
+                    let word = members.iter().all(|id| info.rules.get(&id.text).is_some_and(is_word_rule));
+                    format!("{}.{language}", literal_scope(word))
+                }
+                false => scopes(&styles, language),
+            };
+            token_sets.push(Self { key: name.clone(), scope, regex: alternation(regexes) })
+        }
+        let mut out = vec![];
+        let styled = info.rules.values().filter(|r| !r.styles.is_empty() && !covered.contains(&r.name.text)).collect_vec();
+        for rule in styled {
+            covered.insert(rule.name.text.clone());
+            match rule_regex(rule, info) {
+                Some(regex) => out.push(Self { key: rule.name.text.clone(), scope: scopes(&rule.styles, language), regex }),
+                None => errors.push(unmatchable(rule)),
+            }
+        }
+        out.extend(token_sets);
+        let regex_rules = info.rules.values().filter(|r| !covered.contains(&r.name.text) && is_regex_rule(r)).collect_vec();
+        let mut words = BTreeSet::new();
+        let mut symbols = BTreeSet::new();
+        for rule in info.rules.values().filter(|r| !covered.contains(&r.name.text) && !is_regex_rule(r)) {
+            for_each_text(rule, &mut |text, insensitive| {
+                let regex = literal_regex(text, insensitive);
+                match is_word(text) {
+                    true => words.insert(regex),
+                    false => symbols.insert(regex),
+                };
+            })
+        }
+        if !words.is_empty() {
+            let regex = alternation(words.into_iter().collect());
+            out.push(Self { key: "keywords".to_string(), scope: format!("{}.{language}", literal_scope(true)), regex })
+        }
+        if !symbols.is_empty() {
+            let regex = alternation(symbols.into_iter().collect());
+            out.push(Self { key: "operators".to_string(), scope: format!("{}.{language}", literal_scope(false)), regex })
+        }
+        for rule in regex_rules {
+            if let Some(regex) = rule_regex(rule, info) {
+                let scope = format!("constant.other.{}.{language}", rule.name.text.to_case(Case::Kebab));
+                out.push(Self { key: rule.name.text.clone(), scope, regex })
+            }
+        }
+        out
+    }
+}
+
+fn unmatchable(rule: &GrammarRule) -> YggdrasilError {
+    let message = format!("rule `{}` can't be matched by a regex, it's not highlighted", rule.name.text);
+    YggdrasilError::syntax_error(message, rule.range.clone())
+}
+
+/// The TextMate scopes of the style names, unknown names are used as scopes directly
+fn scopes(styles: &[String], language: &str) -> String {
+    fn scope(style: &str) -> &str {
+        match style {
+            "keyword" => "keyword.control",
+            "operator" => "keyword.operator",
+            "comment" => "comment",
+            "string" | "text" => "string.quoted",
+            "escape" => "constant.character.escape",
+            "number" => "constant.numeric",
+            "constant" => "constant.language",
+            "function" => "entity.name.function",
+            "field" => "variable.other.member",
+            "type" => "entity.name.type",
+            "annotation" => "storage.type.annotation",
+            _ => style,
+        }
+    }
+    styles.iter().map(|s| format!("{}.{language}", scope(s))).join(" ")
+}
+
+fn literal_scope(word: bool) -> &'static str {
+    match word {
+        true => "keyword.other",
+        false => "keyword.operator",
+    }
+}
+
+/// The longest alternative is tried first, so that `==` is not matched as `=`
+fn alternation(mut regexes: Vec<String>) -> String {
+    regexes.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    regexes.dedup();
+    match regexes.as_slice() {
+        [one] => one.clone(),
+        _ => format!("(?:{})", regexes.join("|")),
+    }
+}
+
+fn is_word(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_alphabetic() || c == '_') && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+fn is_word_rule(rule: &GrammarRule) -> bool {
+    match &rule.body {
+        GrammarBody::Class { term: YggdrasilExpression { body: ExpressionBody::Text(t), .. } } => is_word(&t.text),
+        _ => false,
+    }
+}
+
+fn is_regex_rule(rule: &GrammarRule) -> bool {
+    matches!(&rule.body, GrammarBody::Class { term: YggdrasilExpression { body: ExpressionBody::Regex(_), .. } })
+}
+
+/// Keywords only match whole words, `in` is not highlighted in `index`
+fn literal_regex(text: &str, insensitive: bool) -> String {
+    let mut w = escape(text);
+    if insensitive {
+        w = format!("(?i:{w})")
+    }
+    if is_word(text) {
+        w = format!("\\b{w}\\b")
+    }
+    w
+}
+
+fn escape(text: &str) -> String {
+    let mut w = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' | '.' | '^' | '$' | '|' | '?' | '*' | '+' | '(' | ')' | '[' | ']' | '{' | '}' | '/' | '-' | '#' => {
+                w.push('\\');
+                w.push(c)
+            }
+            '\n' => w.push_str("\\n"),
+            '\r' => w.push_str("\\r"),
+            '\t' => w.push_str("\\t"),
+            _ => w.push(c),
+        }
+    }
+    w
+}
+
+fn rule_regex(rule: &GrammarRule, info: &GrammarInfo) -> Option<String> {
+    match &rule.body {
+        GrammarBody::Class { term: YggdrasilExpression { body: ExpressionBody::Text(t), .. } } => {
+            Some(literal_regex(&t.text, t.insensitive))
+        }
+        _ => body_regex(rule, info, &mut vec![]),
+    }
+}
+
+/// `visiting` stops the recursive rules, which can't be matched by a regex
+fn body_regex(rule: &GrammarRule, info: &GrammarInfo, visiting: &mut Vec<String>) -> Option<String> {
+    if visiting.contains(&rule.name.text) {
+        return None;
+    }
+    visiting.push(rule.name.text.clone());
+    let out = match &rule.body {
+        GrammarBody::Class { term } => expression_regex(term, info, visiting),
+        GrammarBody::Union { branches } => {
+            let branches: Option<Vec<_>> = branches.iter().map(|v| expression_regex(&v.branch, info, visiting)).collect();
+            branches.map(|v| format!("(?:{})", v.join("|")))
+        }
+        GrammarBody::Climb { .. } => None,
+    };
+    visiting.pop();
+    out
+}
+
+fn expression_regex(expr: &YggdrasilExpression, info: &GrammarInfo, visiting: &mut Vec<String>) -> Option<String> {
+    let out = match &expr.body {
+        ExpressionBody::Choice(v) => {
+            let branches: Option<Vec<_>> = v.branches.iter().map(|e| expression_regex(e, info, visiting)).collect();
+            format!("(?:{})", branches?.join("|"))
+        }
+        ExpressionBody::Concat(v) => {
+            let sequence: Option<Vec<_>> = v.sequence.iter().map(|e| expression_regex(e, info, visiting)).collect();
+            sequence?.concat()
+        }
+        ExpressionBody::Unary(v) => {
+            let mut w = expression_regex(&v.base, info, visiting)?;
+            for o in &v.operators {
+                w = match o {
+                    YggdrasilOperator::Positive => format!("(?={w})"),
+                    YggdrasilOperator::Negative => format!("(?!{w})"),
+                    YggdrasilOperator::RepeatsBetween { min: 0, max: 1 } => format!("(?:{w})?"),
+                    YggdrasilOperator::RepeatsBetween { min: 0, max: u32::MAX } => format!("(?:{w})*"),
+                    YggdrasilOperator::RepeatsBetween { min: 1, max: u32::MAX } => format!("(?:{w})+"),
+                    YggdrasilOperator::RepeatsBetween { min, max: u32::MAX } => format!("(?:{w}){{{min},}}"),
+                    YggdrasilOperator::RepeatsBetween { min, max } => format!("(?:{w}){{{min},{max}}}"),
+                    YggdrasilOperator::Boxing
+                    | YggdrasilOperator::Recursive
+                    | YggdrasilOperator::Label(_)
+                    | YggdrasilOperator::Error(_) => w,
+                }
+            }
+            w
+        }
+        ExpressionBody::Rule(r) => body_regex(info.rules.get(&r.name.text)?, info, visiting)?,
+        ExpressionBody::Text(t) => match t.insensitive {
+            true => format!("(?i:{})", escape(&t.text)),
+            false => escape(&t.text),
+        },
+        ExpressionBody::Cut => String::new(),
+        ExpressionBody::CharacterAny => ".".to_string(),
+        ExpressionBody::CharacterRestOfLine => "[^\\n]*".to_string(),
+        ExpressionBody::CharacterRange(r) => format!("[{}-{}]", escape(&r.start().to_string()), escape(&r.end().to_string())),
+        ExpressionBody::Regex(r) => format!("(?:{})", r.raw),
+        ExpressionBody::Call(_) | ExpressionBody::Ignored | ExpressionBody::Integer(_) | ExpressionBody::Boolean(_) => {
+            return None;
+        }
+    };
+    Some(out)
+}
+
+/// The text literals in the rule, with their case insensitivity
+fn for_each_text(rule: &GrammarRule, f: &mut impl FnMut(&str, bool)) {
+    fn visit(expr: &YggdrasilExpression, f: &mut impl FnMut(&str, bool)) {
+        match &expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter().for_each(|e| visit(e, f)),
+            ExpressionBody::Concat(v) => v.sequence.iter().for_each(|e| visit(e, f)),
+            ExpressionBody::Unary(v) => visit(&v.base, f),
+            ExpressionBody::Text(t) if !t.text.is_empty() => f(&t.text, t.insensitive),
+            _ => {}
+        }
+    }
+    match &rule.body {
+        GrammarBody::Class { term } => visit(term, f),
+        GrammarBody::Union { branches } | GrammarBody::Climb { priority: branches } => {
+            branches.iter().for_each(|v| visit(&v.branch, f))
+        }
+    }
+}
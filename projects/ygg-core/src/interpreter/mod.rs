@@ -0,0 +1,354 @@
+use convert_case::{Case, Casing};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+    rc::Rc,
+};
+use crate::codegen::target_rust::literal_set;
+use yggdrasil_error::YggdrasilError;
+use yggdrasil_ir::{
+    grammar::GrammarInfo,
+    nodes::{ExpressionBody, YggdrasilExpression, YggdrasilOperator},
+    rule::{ClimbOperator, ClimbPosition, GrammarBody, GrammarRule},
+    IndexMap,
+};
+use yggdrasil_rt::{state, state_recover, OutputResult, Regex, State, TextSpan, TextTrie, TokenTree, YggdrasilRule};
+
+type Input<'i> = Box<State<'i, DynamicRule>>;
+type Output<'i> = Result<Box<State<'i, DynamicRule>>, Box<State<'i, DynamicRule>>>;
+
+/// A rule of the interpreted grammar, identified by its name
+#[derive(Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct DynamicRule {
+    name: Rc<str>,
+    ignore: bool,
+}
+
+impl Debug for DynamicRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl Display for DynamicRule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+impl YggdrasilRule for DynamicRule {
+    fn is_ignore(&self) -> bool {
+        self.ignore
+    }
+    fn is_literal(&self) -> bool {
+        self.ignore && matches!(self.name.as_ref(), "IgnoreText" | "IgnoreRegex")
+    }
+}
+
+impl DynamicRule {
+    fn new(name: &str, ignore: bool) -> Self {
+        Self { name: Rc::from(name), ignore }
+    }
+    /// The name of the rule in the grammar, or `IgnoreText`, `IgnoreRegex` and `IgnoreError` for the built-in nodes
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Parse the input with an optimized grammar directly, without generating and compiling the parser
+///
+/// The token tree is the same as the one of the generated parser, except that the rules are [DynamicRule]s.
+/// The grammar should be optimized by [parse_grammar](crate::parse_grammar) first.
+pub struct GrammarInterpreter {
+    rules: IndexMap<String, (GrammarRule, DynamicRule)>,
+    /// Compiled at runtime, keyed by the raw pattern
+    regexes: HashMap<String, Regex>,
+    /// The literal sets matched by a trie, keyed by the texts and the case
+    tries: HashMap<(Vec<String>, bool), TextTrie>,
+    ignored: Vec<String>,
+    ignore_text: DynamicRule,
+    ignore_regex: DynamicRule,
+    error: DynamicRule,
+}
+
+impl GrammarInterpreter {
+    /// Fails if a regex in the grammar can't be compiled
+    pub fn new(grammar: &GrammarInfo) -> Result<Self, YggdrasilError> {
+        let mut rules = IndexMap::default();
+        let mut regexes = HashMap::new();
+        let mut tries = HashMap::new();
+        for rule in grammar.rules.values() {
+            match &rule.body {
+                GrammarBody::Class { term } => {
+                    find_regex(term, &mut regexes)?;
+                    find_trie(term, &mut tries)
+                }
+                GrammarBody::Union { branches } | GrammarBody::Climb { priority: branches } => {
+                    branches.iter().try_for_each(|v| find_regex(&v.branch, &mut regexes))?;
+                    branches.iter().for_each(|v| find_trie(&v.branch, &mut tries))
+                }
+            }
+            rule.recover.iter().try_for_each(|e| find_regex(e, &mut regexes))?;
+            rule.recover.iter().for_each(|e| find_trie(e, &mut tries));
+            rules.insert(rule.name.text.clone(), (rule.clone(), DynamicRule::new(&rule.name.text, rule.ignored)));
+        }
+        Ok(Self {
+            rules,
+            regexes,
+            tries,
+            ignored: grammar.ignored_rules().into_iter().map(|r| r.name.text).collect(),
+            ignore_text: DynamicRule::new("IgnoreText", true),
+            ignore_regex: DynamicRule::new("IgnoreRegex", true),
+            error: DynamicRule::new("IgnoreError", true),
+        })
+    }
+    /// Get the rule by name
+    pub fn get_rule(&self, name: &str) -> Option<&DynamicRule> {
+        self.rules.get(name).map(|(_, rule)| rule)
+    }
+    /// Parse the input from the rule with the given name
+    pub fn parse_cst<'i>(&self, input: &'i str, rule: &str) -> OutputResult<'i, DynamicRule> {
+        if !self.rules.contains_key(rule) {
+            return Err(yggdrasil_rt::YggdrasilError::missing_rule(
+                DynamicRule::new(rule, false),
+                TextSpan::new(input, 0, 0).unwrap(),
+            ));
+        }
+        state(input, |s| self.parse_rule(s, rule))
+    }
+    /// Parse the input from the rule with the given name, the errors are collected by the `recover` rules
+    pub fn parse_cst_recover<'i>(
+        &self,
+        input: &'i str,
+        rule: &str,
+    ) -> (TokenTree<'i, DynamicRule>, Vec<yggdrasil_rt::YggdrasilError<DynamicRule>>) {
+        state_recover(input, |s| self.parse_rule(s, rule))
+    }
+    fn parse_rule<'i>(&self, state: Input<'i>, name: &str) -> Output<'i> {
+        let (rule, id) = match self.rules.get(name) {
+            Some(s) => s,
+            None => return Err(state),
+        };
+        let f = |s| self.recover_method(s, rule, id.clone());
+        match (&rule.label, &rule.error) {
+            (None, None) => f(state),
+            (Some(label), None) => state.label(label, f),
+            (None, Some(message)) => state.error(message, f),
+            (Some(label), Some(message)) => state.label(label, |s| s.error(message, f)),
+        }
+    }
+    fn recover_method<'i>(&self, state: Input<'i>, rule: &GrammarRule, id: DynamicRule) -> Output<'i> {
+        if rule.recover.is_empty() {
+            return self.rule_method(state, rule, id.clone(), |s| self.parse_body(s, rule));
+        }
+        state.recover(
+            self.error.clone(),
+            |s| self.rule_method(s, rule, id.clone(), |s| self.parse_body(s, rule)),
+            |s| self.parse_recover(s, rule),
+        )
+    }
+    fn rule_method<'i, F>(&self, state: Input<'i>, rule: &GrammarRule, id: DynamicRule, f: F) -> Output<'i>
+    where
+        F: Fn(Input<'i>) -> Output<'i>,
+    {
+        if rule.left_recursive {
+            state.rule_left(id, f)
+        }
+        else if rule.memo {
+            state.rule_memo(id, f)
+        }
+        else {
+            state.rule(id, f)
+        }
+    }
+    fn parse_body<'i>(&self, state: Input<'i>, rule: &GrammarRule) -> Output<'i> {
+        match &rule.body {
+            GrammarBody::Class { term } => self.parse_expression(state, term, true),
+            GrammarBody::Union { branches } => {
+                first_match(state, branches, |s, variant| self.parse_expression(s, &variant.branch, false))
+            }
+            GrammarBody::Climb { .. } => self.parse_climb(state, rule, 0),
+        }
+    }
+    /// Rules are only looked ahead, the next statement shouldn't be swallowed by the error
+    fn parse_recover<'i>(&self, state: Input<'i>, rule: &GrammarRule) -> Output<'i> {
+        first_match(state, &rule.recover, |s, sync| match sync.is_rule() {
+            true => s.lookahead(true, |s| self.parse_expression(s, sync, false)),
+            false => self.parse_expression(s, sync, true),
+        })
+    }
+    /// Precedence climbing, the same as the generated `climb_*` functions
+    fn parse_climb<'i>(&self, state: Input<'i>, rule: &GrammarRule, min: u32) -> Output<'i> {
+        let id = &self.rules[&rule.name.text].1;
+        let operators = rule.climb_operators();
+        let start = state.token_index();
+        let nuds = operators.iter().filter(|o| matches!(o.position, ClimbPosition::Atomic | ClimbPosition::Prefix));
+        let mut state = first_match(state, nuds, |s, o| {
+            s.rule(id.clone(), |s| self.parse_operator(s, rule, o)).and_then(|s| s.tag_node(variant_name(o)))
+        })?;
+        let leds = operators
+            .iter()
+            .filter(|o| matches!(o.position, ClimbPosition::Postfix | ClimbPosition::Infix))
+            .collect::<Vec<_>>();
+        if leds.is_empty() {
+            return Ok(state);
+        }
+        loop {
+            state = match first_match(state, &leds, |s, o| {
+                if o.priority < min {
+                    return Err(s);
+                }
+                let lhs = o.lhs.and_then(|e| e.tag.as_ref()).map(|t| t.text.to_case(Case::Snake));
+                s.climb(start, id.clone(), lhs.unwrap_or("lhs".to_string()), variant_name(o), |s| {
+                    self.parse_operator(s, rule, o)
+                })
+            }) {
+                Ok(s) => s,
+                // stopping here is the last alternative
+                Err(s) => return s.alternative(Ok),
+            }
+        }
+    }
+    /// The operator and the right operand, the left operand is already parsed
+    fn parse_operator<'i>(&self, state: Input<'i>, rule: &GrammarRule, operator: &ClimbOperator) -> Output<'i> {
+        state.sequence(|s| {
+            let mut s = operator.operator.iter().try_fold(s, |s, item| self.parse_expression(s, item, false))?;
+            if let Some(rhs) = operator.rhs {
+                let id = self.rules[&rule.name.text].1.clone();
+                s = s.rule(id, |s| self.parse_climb(s, rule, operator.rhs_priority()))?;
+                if let Some(tag) = &rhs.tag {
+                    s = s.tag_node(tag.text.to_case(Case::Snake))?;
+                }
+            }
+            Ok(s)
+        })
+    }
+    /// The same as `NodeExt::write` in the rust codegen, texts out of the root are wrapped by the built-in nodes
+    fn parse_expression<'i>(&self, state: Input<'i>, expr: &YggdrasilExpression, root: bool) -> Output<'i> {
+        let state = match &expr.body {
+            // a literal set in the root has no `IgnoreText` node, see `literal_set`
+            ExpressionBody::Choice(v) => match literal_set(&v.branches) {
+                Some((texts, insensitive)) => {
+                    let trie = &self.tries[&(texts.into_iter().map(String::from).collect(), insensitive)];
+                    self.builtin(state, root, |s| s.match_trie(trie))
+                }
+                None => first_match(state, &v.branches, |s, e| self.parse_expression(s, e, false)),
+            },
+            ExpressionBody::Concat(v) => {
+                state.sequence(|s| v.sequence.iter().try_fold(s, |s, e| self.parse_expression(s, e, false)))
+            }
+            ExpressionBody::Unary(v) => self.parse_unary(state, &v.operators, &v.base),
+            ExpressionBody::Ignored => self.parse_ignore(state),
+            ExpressionBody::Cut => state.cut(),
+            // the macro calls that can't be expanded never match
+            ExpressionBody::Call(_) => Err(state),
+            ExpressionBody::Rule(r) => self.parse_rule(state, &r.name.text),
+            ExpressionBody::Text(v) => self.builtin(state, root, |s| s.match_string(&v.text, v.insensitive)),
+            ExpressionBody::Regex(r) => match self.regexes.get(&r.raw) {
+                Some(regex) if root => state.match_regex(regex, &r.raw),
+                Some(regex) => state.rule(self.ignore_regex.clone(), |s| s.match_regex(regex, &r.raw)),
+                None => Err(state),
+            },
+            ExpressionBody::CharacterAny => self.builtin(state, root, |s| s.match_char_if(|_| true)),
+            // the line break is not consumed
+            ExpressionBody::CharacterRestOfLine => self.builtin(state, root, |s| s.skip_until(&["\n", "\r"])),
+            // `match_range` treats the range as inclusive
+            ExpressionBody::CharacterRange(v) => self.builtin(state, root, |s| s.match_range(*v.start()..*v.end())),
+            ExpressionBody::Boolean(true) => Ok(state),
+            ExpressionBody::Boolean(false) => Err(state),
+            ExpressionBody::Integer(v) => self.builtin(state, root, |s| s.match_string(&v.to_string(), false)),
+        }?;
+        match &expr.tag {
+            Some(s) => state.tag_node(s.text.to_case(Case::Snake)),
+            None => Ok(state),
+        }
+    }
+    /// The first operator is the outermost one
+    fn parse_unary<'i>(&self, state: Input<'i>, operators: &[YggdrasilOperator], base: &YggdrasilExpression) -> Output<'i> {
+        let (head, rest) = match operators {
+            [head, rest @ ..] => (head, rest),
+            [] => return self.parse_expression(state, base, false),
+        };
+        match head {
+            YggdrasilOperator::Positive => state.lookahead(true, |s| self.parse_unary(s, rest, base)),
+            YggdrasilOperator::Negative => state.lookahead(false, |s| self.parse_unary(s, rest, base)),
+            YggdrasilOperator::RepeatsBetween { min: 0, max: 1 } => state.optional(|s| self.parse_unary(s, rest, base)),
+            YggdrasilOperator::RepeatsBetween { min, max } => state.repeat(*min..*max, |s| self.parse_unary(s, rest, base)),
+            // only the ast is boxed, the input is matched as a group
+            YggdrasilOperator::Boxing | YggdrasilOperator::Recursive => state.sequence(|s| self.parse_unary(s, rest, base)),
+            YggdrasilOperator::Label(label) => state.label(label, |s| self.parse_unary(s, rest, base)),
+            YggdrasilOperator::Error(message) => state.error(message, |s| self.parse_unary(s, rest, base)),
+        }
+    }
+    /// All rules ignored in ast mode
+    fn parse_ignore<'i>(&self, state: Input<'i>) -> Output<'i> {
+        if self.ignored.is_empty() {
+            return Ok(state);
+        }
+        state.repeat(0..u32::MAX, |s| first_match(s, &self.ignored, |s, name| self.parse_rule(s, name)))
+    }
+    fn builtin<'i, F>(&self, state: Input<'i>, root: bool, f: F) -> Output<'i>
+    where
+        F: FnOnce(Input<'i>) -> Output<'i>,
+    {
+        match root {
+            true => f(state),
+            false => state.rule(self.ignore_text.clone(), f),
+        }
+    }
+}
+
+/// Ordered choice, returns the first branch that matches
+fn first_match<'i, T, F>(mut state: Input<'i>, branches: impl IntoIterator<Item = T>, mut f: F) -> Output<'i>
+where
+    F: FnMut(Input<'i>, T) -> Output<'i>,
+{
+    for branch in branches {
+        // the branches are skipped after a final failure, see `State::cut`
+        match state.alternative(|s| f(s, branch)) {
+            Ok(s) => return Ok(s),
+            Err(s) => state = s,
+        }
+    }
+    Err(state)
+}
+
+fn variant_name(operator: &ClimbOperator) -> String {
+    match &operator.variant.tag {
+        Some(s) => s.text.to_case(Case::Snake),
+        None => unreachable!("have you run remark?"),
+    }
+}
+
+fn find_trie(info: &YggdrasilExpression, out: &mut HashMap<(Vec<String>, bool), TextTrie>) {
+    match &info.body {
+        ExpressionBody::Choice(node) => match literal_set(&node.branches) {
+            Some((texts, insensitive)) => {
+                let trie = TextTrie::new(&texts, insensitive);
+                out.insert((texts.into_iter().map(String::from).collect(), insensitive), trie);
+            }
+            None => node.branches.iter().for_each(|e| find_trie(e, out)),
+        },
+        ExpressionBody::Concat(node) => node.sequence.iter().for_each(|e| find_trie(e, out)),
+        ExpressionBody::Unary(node) => find_trie(&node.base, out),
+        _ => {}
+    }
+}
+
+fn find_regex(info: &YggdrasilExpression, out: &mut HashMap<String, Regex>) -> Result<(), YggdrasilError> {
+    match &info.body {
+        ExpressionBody::Choice(node) => node.branches.iter().try_for_each(|e| find_regex(e, out)),
+        ExpressionBody::Concat(node) => node.sequence.iter().try_for_each(|e| find_regex(e, out)),
+        ExpressionBody::Unary(node) => find_regex(&node.base, out),
+        ExpressionBody::Regex(regex) if !out.contains_key(&regex.raw) => match Regex::new(&regex.pattern()) {
+            Ok(o) => {
+                out.insert(regex.raw.clone(), o);
+                Ok(())
+            }
+            Err(e) => {
+                Err(YggdrasilError::syntax_error(format!("regex `{}` can't be compiled: {e}", regex.raw), regex.span.clone()))
+            }
+        },
+        _ => Ok(()),
+    }
+}
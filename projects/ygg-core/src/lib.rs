@@ -3,11 +3,13 @@
 
 // noinspection DuplicatedCode
 pub mod codegen;
+#[cfg(feature = "dynamic")]
+pub mod interpreter;
 pub mod optimize;
 
 mod utils;
 
-pub use crate::utils::{parse_grammar, parse_grammar_raw};
+pub use crate::utils::{load_grammar, optimize_grammar, parse_grammar, parse_grammar_raw, GrammarLinker};
 pub use yggdrasil_ir::{
     grammar::GrammarInfo,
     nodes::{ChoiceExpression, ConcatExpression, ExpressionBody, UnaryExpression, YggdrasilExpression, YggdrasilOperator},
@@ -10,7 +10,6 @@ use std::{
     ops::{Add, AddAssign},
 };
 
-mod cli;
 mod item;
 
 pub static HINT_MANAGER: SyncLazy<HintManager> = SyncLazy::new(|| HintManager::default());
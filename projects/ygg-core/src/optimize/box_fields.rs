@@ -0,0 +1,93 @@
+use super::*;
+use std::collections::HashMap;
+use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
+
+/// Box the fields that make a node contain itself, such as `class A { a:B? }` with `class B { b:A }`
+///
+/// Only `one` and `optional` fields are stored inline, a cycle through them would have an infinite size.
+/// The fields that close a cycle in the depth first walk are boxed, `Vec` fields already break the cycle.
+///
+/// Must run after [RemarkTags], the tags decide the fields.
+#[derive(Default)]
+pub struct BoxFields {
+    /// `(rule, branch, field) -> type` of the inline fields
+    edges: HashMap<String, Vec<(usize, String, String)>>,
+    /// rules on the current path
+    stack: Vec<String>,
+    visited: HashSet<String>,
+    /// `(rule, branch, field)` to be boxed
+    boxed: HashSet<(String, usize, String)>,
+}
+
+impl CodeOptimizer for BoxFields {
+    fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
+        self.edges.clear();
+        self.visited.clear();
+        self.boxed.clear();
+        for rule in info.rules.values() {
+            let mut edges = vec![];
+            for (index, branch) in branches(&rule.body).into_iter().enumerate() {
+                for field in branch.field_map() {
+                    let inline = field.count.is_one() || field.count.is_optional();
+                    if inline && !field.boxing && info.rules.contains_key(&field.rhs) {
+                        edges.push((index, field.lhs, field.rhs))
+                    }
+                }
+            }
+            self.edges.insert(rule.name.text.clone(), edges);
+        }
+        for name in info.rules.keys() {
+            self.visit(name);
+        }
+        let mut out = info.clone();
+        for rule in out.rules.values_mut() {
+            let mut index = 0;
+            rule.body.for_each(|e| {
+                self.box_field(e, &rule.name.text, index);
+                index += 1;
+            });
+        }
+        Validation::Success { value: out, diagnostics: vec![] }
+    }
+}
+
+impl BoxFields {
+    fn visit(&mut self, rule: &str) {
+        if !self.visited.insert(rule.to_string()) {
+            return;
+        }
+        self.stack.push(rule.to_string());
+        for (index, field, target) in self.edges.get(rule).cloned().unwrap_or_default() {
+            match self.stack.contains(&target) {
+                true => {
+                    self.boxed.insert((rule.to_string(), index, field));
+                }
+                false => self.visit(&target),
+            }
+        }
+        self.stack.pop();
+    }
+    fn box_field(&self, expr: &mut YggdrasilExpression, rule: &str, index: usize) {
+        match &mut expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter_mut().for_each(|e| self.box_field(e, rule, index)),
+            ExpressionBody::Concat(v) => v.sequence.iter_mut().for_each(|e| self.box_field(e, rule, index)),
+            ExpressionBody::Unary(v) => self.box_field(&mut v.base, rule, index),
+            ExpressionBody::Rule(r) => {
+                if let Some(tag) = &expr.tag {
+                    r.boxed |= self.boxed.contains(&(rule.to_string(), index, tag.text.clone()))
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The expressions that own a field map, the branches of a union or climb have their own fields
+fn branches(body: &GrammarBody) -> Vec<&YggdrasilExpression> {
+    match body {
+        GrammarBody::Class { term } => vec![term],
+        GrammarBody::Union { branches } => branches.iter().map(|v| &v.branch).collect(),
+        GrammarBody::Climb { priority } => priority.iter().map(|v| &v.branch).collect(),
+    }
+}
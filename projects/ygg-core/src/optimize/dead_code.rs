@@ -1,44 +1,71 @@
 use super::*;
 use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
 
+/// Remove all rules that can't be reached from the roots
+///
+/// The roots are `entry` rules, exported rules, `hide` rules and ignored rules, a grammar without
+/// any `entry` or export is kept as it is and reported.
+#[derive(Default)]
 pub struct DeadCodeEliminator {
-    pub panic: bool,
     used: HashSet<String>,
     new: HashSet<String>,
     unvisited: HashSet<String>,
 }
 
-impl Default for DeadCodeEliminator {
-    fn default() -> Self {
-        Self { used: Default::default(), new: Default::default(), unvisited: Default::default(), panic: true }
-    }
-}
-
 impl CodeOptimizer for DeadCodeEliminator {
-    // TODO: Tri-Color mark and sweep algorithm
+    /// Tri-color mark and sweep, `new` is grey and `used` is black
     fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
         let mut errors = vec![];
-        self.find_entry(info);
-        self.find_unvisited();
-        Validation::Success { value: info.clone(), diagnostics: errors }
+        self.clear();
+        if !self.find_entry(info) {
+            if !info.rules.is_empty() {
+                let message = format!("grammar `{}` has no `entry` rule, unused rules are kept", info.name.text);
+                errors.push(YggdrasilError::syntax_error(message, info.name.range.clone()))
+            }
+            return Validation::Success { value: info.clone(), diagnostics: errors };
+        }
+        loop {
+            self.find_unvisited();
+            if self.unvisited.is_empty() {
+                break;
+            }
+            for name in take(&mut self.unvisited) {
+                if let Some(rule) = info.rules.get(&name) {
+                    self.mark_rule(rule);
+                }
+                self.used.insert(name);
+            }
+        }
+        // inline rules are expected to be unused after inlining
+        for rule in info.rules.values().filter(|r| !self.used.contains(&r.name.text) && !r.auto_inline) {
+            let message = format!("rule `{}` is never used", rule.name.text);
+            errors.push(YggdrasilError::syntax_error(message, rule.range.clone()))
+        }
+        let mut out = info.clone();
+        out.rules = self.find_needed(info);
+        Validation::Success { value: out, diagnostics: errors }
     }
 }
 
 impl DeadCodeEliminator {
-    fn find_entry(&mut self, info: &GrammarInfo) {
-        todo!()
-        // for (_, rule) in &info.rules {
-        //     if info.exports.contains(&rule.name) || rule.entry {
-        //         self.new.insert(rule.name.to_owned());
-        //     }
-        // }
+    /// Returns false if the grammar has no entry
+    fn find_entry(&mut self, info: &GrammarInfo) -> bool {
+        let mut has_entry = false;
+        for rule in info.rules.values() {
+            let exported = info.exports.contains(&rule.name.text);
+            has_entry |= exported || rule.entry;
+            if exported || rule.entry || rule.hide || rule.ignored {
+                self.new.insert(rule.name.text.to_owned());
+            }
+        }
+        has_entry
     }
     fn find_unvisited(&mut self) {
         self.unvisited.clear();
         for rule in self.new.difference(&self.used) {
             self.unvisited.insert(rule.to_owned());
         }
-        // println!("未访问节点: {:?}", self.unvisited);
         self.new.clear();
     }
     fn find_needed(&self, info: &GrammarInfo) -> IndexMap<String, GrammarRule> {
@@ -48,4 +75,26 @@ impl DeadCodeEliminator {
         self.used.clear();
         self.new.clear();
     }
+    fn mark_rule(&mut self, rule: &GrammarRule) {
+        match &rule.body {
+            GrammarBody::Class { term } => self.mark_node(term),
+            GrammarBody::Union { branches } => branches.iter().for_each(|v| self.mark_node(&v.branch)),
+            GrammarBody::Climb { priority } => priority.iter().for_each(|v| self.mark_node(&v.branch)),
+        }
+        rule.recover.iter().for_each(|e| self.mark_node(e));
+    }
+    fn mark_node(&mut self, info: &YggdrasilExpression) {
+        match &info.body {
+            ExpressionBody::Choice(node) => node.branches.iter().for_each(|e| self.mark_node(e)),
+            ExpressionBody::Concat(node) => node.sequence.iter().for_each(|e| self.mark_node(e)),
+            ExpressionBody::Unary(node) => self.mark_node(&node.base),
+            ExpressionBody::Call(node) => node.arguments.iter().for_each(|a| self.mark_node(&a.value)),
+            ExpressionBody::Rule(node) => {
+                if !self.used.contains(&node.name.text) {
+                    self.new.insert(node.name.text.clone());
+                }
+            }
+            _ => {}
+        }
+    }
 }
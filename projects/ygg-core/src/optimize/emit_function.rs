@@ -1,14 +1,36 @@
+use std::ops::Range;
 use yggdrasil_error::Validation;
+use yggdrasil_ir::{
+    data::{YggdrasilRegex, YggdrasilText},
+    nodes::YggdrasilOperator,
+    rule::YggdrasilMacroCall,
+};
 use super::*;
 
+/// Expand the macro calls with the built-in macros and the `macro` rules
+///
+/// | Call | Expansion |
+/// | :-: | :-: |
+/// | `@sep_by(A, ',')` | `(A (',' A)*)?` |
+/// | `@sep_by1(A, ',')` | `A (',' A)*` |
+/// | `@parens(A)` | `'(' A ')'` |
+/// | `@keyword('if', 'else')` | `/if\b/ \| /else\b/` |
+/// | `@eof()` | `!ANY` |
+/// | `@cut()` | the failures after it in the sequence are final |
+/// | `@label("expression", A)` | `A`, reported as `expression` when it fails |
+/// | `@error("expected a closing brace", '}')` | `'}'`, reported with the message when it fails |
+///
+/// A `macro` rule with the same name as a built-in macro takes precedence.
 pub struct EmitFunction {
     functions: IndexMap<String, FunctionRule>,
+    /// macros being expanded
+    stack: Vec<String>,
     errors: Vec<YggdrasilError>,
 }
 
 impl Default for EmitFunction {
     fn default() -> Self {
-        Self { functions: Default::default(), errors: vec![] }
+        Self { functions: Default::default(), stack: vec![], errors: vec![] }
     }
 }
 
@@ -16,22 +38,202 @@ impl CodeOptimizer for EmitFunction {
     fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
         let mut rules = info.rules.clone();
         self.functions = info.functions.clone();
-        match self.emit(&mut rules) {
-            Ok(_) => {}
-            Err(e) => return Validation::Failure { fatal: e, diagnostics: vec![] },
-        }
+        self.emit(&mut rules);
         // Reset Progress
         let grammar = GrammarInfo { rules: take(&mut rules), functions: Default::default(), ..info.clone() };
-        let errors = take(&mut self.errors);
-        Validation::Success { value: grammar, diagnostics: errors }
+        let mut errors = take(&mut self.errors);
+        match errors.is_empty() {
+            true => Validation::Success { value: grammar, diagnostics: errors },
+            false => {
+                let fatal = errors.remove(0);
+                Validation::Failure { fatal, diagnostics: errors }
+            }
+        }
     }
 }
 
 impl EmitFunction {
-    fn emit(&mut self, rules: &mut IndexMap<String, GrammarRule>) -> Result<(), YggdrasilError> {
+    fn emit(&mut self, rules: &mut IndexMap<String, GrammarRule>) {
         for (_, rule) in rules.iter_mut() {
-            // self.emit_expression(&mut rule.body)?;
+            rule.body.for_each(|e| self.emit_node(e));
+            rule.recover.iter_mut().for_each(|e| self.emit_node(e));
+        }
+    }
+    /// The calls that can't be expanded are reported, and fail the pass
+    fn emit_node(&mut self, info: &mut YggdrasilExpression) {
+        match &mut info.body {
+            ExpressionBody::Choice(node) => node.branches.iter_mut().for_each(|e| self.emit_node(e)),
+            ExpressionBody::Concat(node) => node.sequence.iter_mut().for_each(|e| self.emit_node(e)),
+            ExpressionBody::Unary(node) => self.emit_node(&mut node.base),
+            ExpressionBody::Call(node) => {
+                node.arguments.iter_mut().for_each(|a| self.emit_node(&mut a.value));
+                let result = match self.functions.get(&node.name.to_string()).cloned() {
+                    Some(s) => self.emit_user(&s, node),
+                    None => emit_builtin(node),
+                };
+                let mut body = match result {
+                    Ok(o) => o,
+                    Err(e) => return self.errors.push(e),
+                };
+                // `tag:@call()` is kept on the body, a tagged body is wrapped
+                if let Some(tag) = info.tag.take() {
+                    if body.tag.is_some() {
+                        body = ConcatExpression { sequence: vec![body] }.into();
+                    }
+                    body.tag = Some(tag);
+                }
+                body.remark |= info.remark;
+                *info = body;
+            }
+            // do nothing
+            _ => {}
+        }
+    }
+    fn emit_user(&mut self, function: &FunctionRule, call: &YggdrasilMacroCall) -> Result<YggdrasilExpression, YggdrasilError> {
+        let name = &function.name.text;
+        if self.stack.contains(name) {
+            let path = self.stack.iter().skip_while(|s| s.ne(&name)).chain([name]).join(" -> ");
+            let message = format!("recursive macro `@{name}` can't be expanded: {path}");
+            return Err(YggdrasilError::syntax_error(message, call.range.clone()));
+        }
+        check_arity(call, function.parameters.len())?;
+        let parameters = function.parameters.iter().map(|p| p.text.as_str());
+        let arguments = parameters.zip(call.arguments.iter().map(|a| &a.value)).collect_vec();
+        let mut body = function.body.clone();
+        substitute(&mut body, &arguments);
+        self.stack.push(name.clone());
+        self.emit_node(&mut body);
+        self.stack.pop();
+        Ok(body)
+    }
+}
+
+fn emit_builtin(call: &YggdrasilMacroCall) -> Result<YggdrasilExpression, YggdrasilError> {
+    let arguments = call.arguments.iter().map(|a| a.value.clone()).collect_vec();
+    let out = match call.name.to_string().as_str() {
+        "sep_by" => {
+            check_arity(call, 2)?;
+            unary(sep_by(&arguments[0], &arguments[1]), YggdrasilOperator::OPTIONAL)
+        }
+        "sep_by1" => {
+            check_arity(call, 2)?;
+            sep_by(&arguments[0], &arguments[1])
+        }
+        "parens" => {
+            check_arity(call, 1)?;
+            let (lhs, rhs) = (text("(", &call.range), text(")", &call.range));
+            ConcatExpression { sequence: vec![lhs, arguments[0].clone(), rhs] }.into()
+        }
+        "keyword" => {
+            let mut branches = vec![];
+            for argument in &arguments {
+                match &argument.body {
+                    ExpressionBody::Text(v) => branches.push(keyword(v).into()),
+                    _ => {
+                        let message = "macro `@keyword` only accepts string literals".to_string();
+                        return Err(YggdrasilError::syntax_error(message, call.range.clone()));
+                    }
+                }
+            }
+            match branches.len() {
+                0 => {
+                    let message = "macro `@keyword` expects at least 1 argument";
+                    return Err(YggdrasilError::syntax_error(message, call.range.clone()));
+                }
+                1 => branches.remove(0),
+                _ => ChoiceExpression { branches }.into(),
+            }
+        }
+        "eof" => {
+            check_arity(call, 0)?;
+            unary(YggdrasilExpression::any(), YggdrasilOperator::Negative)
+        }
+        "cut" => {
+            check_arity(call, 0)?;
+            YggdrasilExpression::cut()
+        }
+        name @ ("label" | "error") => {
+            check_arity(call, 2)?;
+            let message = match &arguments[0].body {
+                ExpressionBody::Text(v) => v.text.clone(),
+                _ => {
+                    let message = format!("macro `@{name}` expects a string literal first");
+                    return Err(YggdrasilError::syntax_error(message, call.range.clone()));
+                }
+            };
+            let operator = match name {
+                "label" => YggdrasilOperator::Label(message),
+                _ => YggdrasilOperator::Error(message),
+            };
+            unary(arguments[1].clone(), operator)
+        }
+        name => {
+            let message = format!("unknown macro `@{name}`");
+            return Err(YggdrasilError::syntax_error(message, call.range.clone()));
+        }
+    };
+    Ok(out)
+}
+
+fn check_arity(call: &YggdrasilMacroCall, expected: usize) -> Result<(), YggdrasilError> {
+    if call.arguments.len() == expected {
+        return Ok(());
+    }
+    let message = format!("macro `@{}` expects {expected} arguments, found {}", call.name, call.arguments.len());
+    Err(YggdrasilError::syntax_error(message, call.range.clone()))
+}
+
+/// Replace the references to the parameters with the arguments
+fn substitute(info: &mut YggdrasilExpression, arguments: &[(&str, &YggdrasilExpression)]) {
+    match &mut info.body {
+        ExpressionBody::Choice(node) => node.branches.iter_mut().for_each(|e| substitute(e, arguments)),
+        ExpressionBody::Concat(node) => node.sequence.iter_mut().for_each(|e| substitute(e, arguments)),
+        ExpressionBody::Unary(node) => substitute(&mut node.base, arguments),
+        ExpressionBody::Call(node) => node.arguments.iter_mut().for_each(|a| substitute(&mut a.value, arguments)),
+        ExpressionBody::Rule(node) if !node.boxed && !node.inline => {
+            let mut body = match arguments.iter().find(|(name, _)| node.name.text.eq(name)) {
+                Some((_, value)) => (*value).clone(),
+                None => return,
+            };
+            if let Some(tag) = info.tag.take() {
+                if body.tag.is_some() {
+                    body = ConcatExpression { sequence: vec![body] }.into();
+                }
+                body.tag = Some(tag);
+            }
+            body.remark |= info.remark;
+            *info = body;
+        }
+        _ => {}
+    }
+}
+
+/// `item (sep item)*`
+fn sep_by(item: &YggdrasilExpression, sep: &YggdrasilExpression) -> YggdrasilExpression {
+    let rest = ConcatExpression { sequence: vec![sep.clone(), item.clone()] };
+    ConcatExpression { sequence: vec![item.clone(), unary(rest.into(), YggdrasilOperator::REPEATS)] }.into()
+}
+
+fn unary(base: YggdrasilExpression, operator: YggdrasilOperator) -> YggdrasilExpression {
+    UnaryExpression { base: Box::new(base), operators: vec![operator] }.into()
+}
+
+fn text(text: &str, range: &Range<usize>) -> YggdrasilExpression {
+    YggdrasilText::new(text, range.clone()).into()
+}
+
+/// The keyword must not be followed by an identifier character, `(?-u:\b)` also works with the dfa
+fn keyword(text: &YggdrasilText) -> YggdrasilRegex {
+    let mut raw = String::new();
+    if text.insensitive {
+        raw.push_str("(?i)");
+    }
+    for c in text.text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            raw.push('\\');
         }
-        Ok(())
+        raw.push(c);
     }
+    raw.push_str("(?-u:\\b)");
+    YggdrasilRegex::new(raw, text.range.clone())
 }
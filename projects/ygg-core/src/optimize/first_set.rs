@@ -0,0 +1,331 @@
+use super::*;
+use std::ops::RangeInclusive;
+use yggdrasil_error::Validation;
+use yggdrasil_ir::{
+    data::YggdrasilText,
+    nodes::YggdrasilOperator,
+    rule::{ClimbPosition, GrammarBody},
+};
+
+/// Compute the FIRST sets and the nullability of all rules, and lint the mistakes hidden by ordered choice
+///
+/// | Grammar | Warning |
+/// | :-: | :-: |
+/// | `'for' \| 'foreach'` | the second branch is shadowed |
+/// | `A? \| B` | the second branch is shadowed, the first never fails |
+/// | `[a-z] \| 'x' B` | the second branch is shadowed |
+/// | `(A?)*` | the body of the repetition matches empty input, loops forever |
+/// | `class A { &B }` | `A` never consumes input |
+///
+/// The grammar is not changed.
+#[derive(Default)]
+pub struct FirstSets {
+    /// Rules that may succeed without consuming input
+    nullable: HashSet<String>,
+    /// Rules that never fail
+    infallible: HashSet<String>,
+    /// Rules that may consume input
+    consuming: HashSet<String>,
+    first: IndexMap<String, FirstSet>,
+    ignored: FirstSet,
+}
+
+/// The characters an expression may start with
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FirstSet {
+    /// Sorted and merged ranges
+    pub chars: Vec<RangeInclusive<char>>,
+    /// Starts with something can't be analyzed, such as regex and macros
+    pub unknown: bool,
+}
+
+impl CodeOptimizer for FirstSets {
+    fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
+        let mut errors = vec![];
+        self.analyze(info);
+        for rule in info.rules.values() {
+            if !self.consuming.contains(&rule.name.text) {
+                let message = format!("rule `{}` never consumes any input", rule.name.text);
+                errors.push(YggdrasilError::syntax_error(message, rule.range.clone()))
+            }
+            match &rule.body {
+                GrammarBody::Class { term } => self.lint_node(term, rule, &mut errors),
+                GrammarBody::Union { branches } => {
+                    let branches = branches.iter().map(|v| &v.branch).collect_vec();
+                    branches.iter().for_each(|e| self.lint_node(e, rule, &mut errors));
+                    self.lint_choice(&branches, rule, &mut errors)
+                }
+                // the operators are tried after the left operand
+                GrammarBody::Climb { priority } => priority.iter().for_each(|v| self.lint_node(&v.branch, rule, &mut errors)),
+            }
+        }
+        Validation::Success { value: info.clone(), diagnostics: errors }
+    }
+}
+
+impl FirstSets {
+    /// Fill the sets of all rules, until nothing changes
+    pub fn analyze(&mut self, info: &GrammarInfo) {
+        *self = Self::default();
+        loop {
+            let mut changed = false;
+            for rule in info.rules.values() {
+                let name = &rule.name.text;
+                let branches = branches(rule);
+                if !self.nullable.contains(name) && branches.iter().any(|e| self.is_nullable(e)) {
+                    changed |= self.nullable.insert(name.clone());
+                }
+                if !self.infallible.contains(name) && branches.iter().any(|e| self.is_infallible(e)) {
+                    changed |= self.infallible.insert(name.clone());
+                }
+                if !self.consuming.contains(name) && branches.iter().any(|e| self.is_consuming(e)) {
+                    changed |= self.consuming.insert(name.clone());
+                }
+                let mut first = FirstSet::default();
+                branches.iter().for_each(|e| first.extend(&self.first_of(e)));
+                if self.first.get(name) != Some(&first) {
+                    self.first.insert(name.clone(), first);
+                    changed = true;
+                }
+            }
+            let mut ignored = FirstSet::default();
+            for rule in info.rules.values().filter(|r| r.ignored) {
+                ignored.extend(&self.first[&rule.name.text]);
+            }
+            changed |= self.ignored != ignored;
+            self.ignored = ignored;
+            if !changed {
+                break;
+            }
+        }
+    }
+    /// May succeed without consuming any input
+    pub fn is_nullable(&self, expr: &YggdrasilExpression) -> bool {
+        match &expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter().any(|e| self.is_nullable(e)),
+            ExpressionBody::Concat(v) => v.sequence.iter().all(|e| self.is_nullable(e)),
+            ExpressionBody::Unary(v) => {
+                let mut nullable = self.is_nullable(&v.base);
+                for o in &v.operators {
+                    match o {
+                        YggdrasilOperator::Positive | YggdrasilOperator::Negative => nullable = true,
+                        YggdrasilOperator::RepeatsBetween { min: 0, .. } => nullable = true,
+                        _ => {}
+                    }
+                }
+                nullable
+            }
+            ExpressionBody::Rule(r) => self.nullable.contains(&r.name.text),
+            ExpressionBody::Text(v) => v.text.is_empty(),
+            ExpressionBody::Ignored | ExpressionBody::Cut => true,
+            _ => false,
+        }
+    }
+    /// Never fails, the branches after it are never tried
+    pub fn is_infallible(&self, expr: &YggdrasilExpression) -> bool {
+        match &expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter().any(|e| self.is_infallible(e)),
+            ExpressionBody::Concat(v) => v.sequence.iter().all(|e| self.is_infallible(e)),
+            ExpressionBody::Unary(v) => {
+                let mut infallible = self.is_infallible(&v.base);
+                // the first operator is the outermost
+                for o in v.operators.iter().rev() {
+                    match o {
+                        YggdrasilOperator::Negative => infallible = false,
+                        YggdrasilOperator::RepeatsBetween { min: 0, .. } => infallible = true,
+                        _ => {}
+                    }
+                }
+                infallible
+            }
+            ExpressionBody::Rule(r) => self.infallible.contains(&r.name.text),
+            ExpressionBody::Text(v) => v.text.is_empty(),
+            ExpressionBody::Ignored | ExpressionBody::Cut => true,
+            _ => false,
+        }
+    }
+    /// May consume some input
+    fn is_consuming(&self, expr: &YggdrasilExpression) -> bool {
+        match &expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter().any(|e| self.is_consuming(e)),
+            ExpressionBody::Concat(v) => v.sequence.iter().any(|e| self.is_consuming(e)),
+            ExpressionBody::Unary(v) => {
+                let lookahead =
+                    v.operators.iter().any(|o| matches!(o, YggdrasilOperator::Positive | YggdrasilOperator::Negative));
+                !lookahead && self.is_consuming(&v.base)
+            }
+            ExpressionBody::Rule(r) => self.consuming.contains(&r.name.text),
+            ExpressionBody::Text(v) => !v.text.is_empty(),
+            ExpressionBody::Integer(_) | ExpressionBody::Boolean(_) | ExpressionBody::Cut => false,
+            _ => true,
+        }
+    }
+    /// The characters the expression may start with
+    pub fn first_of(&self, expr: &YggdrasilExpression) -> FirstSet {
+        let mut out = FirstSet::default();
+        match &expr.body {
+            ExpressionBody::Choice(v) => v.branches.iter().for_each(|e| out.extend(&self.first_of(e))),
+            ExpressionBody::Concat(v) => {
+                for item in &v.sequence {
+                    out.extend(&self.first_of(item));
+                    if !self.is_nullable(item) {
+                        break;
+                    }
+                }
+            }
+            ExpressionBody::Unary(v) => {
+                if !v.operators.iter().any(|o| matches!(o, YggdrasilOperator::Positive | YggdrasilOperator::Negative)) {
+                    out = self.first_of(&v.base)
+                }
+            }
+            ExpressionBody::Rule(r) => out = self.first.get(&r.name.text).cloned().unwrap_or_default(),
+            ExpressionBody::Text(v) => {
+                if let Some(c) = v.text.chars().next() {
+                    match v.insensitive {
+                        true => {
+                            out.insert(c.to_ascii_lowercase()..=c.to_ascii_lowercase());
+                            out.insert(c.to_ascii_uppercase()..=c.to_ascii_uppercase());
+                        }
+                        false => out.insert(c..=c),
+                    }
+                }
+            }
+            ExpressionBody::Ignored => out = self.ignored.clone(),
+            ExpressionBody::CharacterAny => out.insert('\0'..=char::MAX),
+            ExpressionBody::CharacterRange(v) => out.insert(v.clone()),
+            ExpressionBody::Integer(_) | ExpressionBody::Boolean(_) | ExpressionBody::Cut => {}
+            ExpressionBody::CharacterRestOfLine | ExpressionBody::Regex(_) | ExpressionBody::Call(_) => out.unknown = true,
+        }
+        out
+    }
+    fn lint_node(&self, expr: &YggdrasilExpression, rule: &GrammarRule, errors: &mut Vec<YggdrasilError>) {
+        match &expr.body {
+            ExpressionBody::Choice(v) => {
+                v.branches.iter().for_each(|e| self.lint_node(e, rule, errors));
+                self.lint_choice(&v.branches.iter().collect_vec(), rule, errors)
+            }
+            ExpressionBody::Concat(v) => v.sequence.iter().for_each(|e| self.lint_node(e, rule, errors)),
+            ExpressionBody::Unary(v) => {
+                self.lint_node(&v.base, rule, errors);
+                let repeated =
+                    v.operators.iter().any(|o| matches!(o, YggdrasilOperator::RepeatsBetween { max, .. } if *max > 1));
+                if repeated && self.is_nullable(&v.base) {
+                    let message = "the body of the repetition may match empty input, it loops forever";
+                    errors.push(YggdrasilError::syntax_error(message, expr.get_range().unwrap_or(rule.range.clone())))
+                }
+            }
+            _ => {}
+        }
+    }
+    fn lint_choice(&self, branches: &[&YggdrasilExpression], rule: &GrammarRule, errors: &mut Vec<YggdrasilError>) {
+        for (index, branch) in branches.iter().enumerate() {
+            let reason = branches[..index].iter().find_map(|before| self.shadowed_by(before, branch));
+            if let Some(reason) = reason {
+                let message = format!("branch {} of `{}` is never matched, {}", index + 1, rule.name.text, reason);
+                errors.push(YggdrasilError::syntax_error(message, branch.get_range().unwrap_or(rule.range.clone())))
+            }
+        }
+    }
+    /// Whenever `after` matches, `before` matches too
+    fn shadowed_by(&self, before: &YggdrasilExpression, after: &YggdrasilExpression) -> Option<String> {
+        if self.is_infallible(before) {
+            return Some("the previous branch never fails".to_string());
+        }
+        if let ExpressionBody::Text(prefix) = &before.body {
+            let text = literal_prefix(after);
+            if text.iter().any(|t| shadows(prefix, t)) {
+                return Some(format!("{:?} comes first", prefix.text));
+            }
+        }
+        let single = match &before.body {
+            ExpressionBody::CharacterAny | ExpressionBody::CharacterRange(_) => true,
+            ExpressionBody::Text(v) => v.text.chars().count() == 1,
+            _ => false,
+        };
+        if single && !self.is_nullable(after) && self.first_of(before).contains(&self.first_of(after)) {
+            return Some("the first character always matches the previous branch".to_string());
+        }
+        None
+    }
+}
+
+impl FirstSet {
+    /// Insert a range and keep the ranges merged
+    pub fn insert(&mut self, range: RangeInclusive<char>) {
+        if range.is_empty() {
+            return;
+        }
+        self.chars.push(range);
+        self.chars.sort_by_key(|r| *r.start());
+        let mut merged: Vec<RangeInclusive<char>> = Vec::with_capacity(self.chars.len());
+        for range in self.chars.drain(..) {
+            match merged.last_mut() {
+                Some(last) if *range.start() as u32 <= *last.end() as u32 + 1 => {
+                    if range.end() > last.end() {
+                        *last = *last.start()..=*range.end()
+                    }
+                }
+                _ => merged.push(range),
+            }
+        }
+        self.chars = merged;
+    }
+    pub fn extend(&mut self, other: &FirstSet) {
+        other.chars.iter().for_each(|r| self.insert(r.clone()));
+        self.unknown |= other.unknown;
+    }
+    /// All characters of `other` are in this set
+    pub fn contains(&self, other: &FirstSet) -> bool {
+        !other.unknown && other.chars.iter().all(|o| self.chars.iter().any(|r| r.start() <= o.start() && o.end() <= r.end()))
+    }
+}
+
+/// Top level alternatives of a rule
+///
+/// The operators of `climb` are parsed after the left operand, so only atoms and prefixes count.
+pub(super) fn branches(rule: &GrammarRule) -> Vec<&YggdrasilExpression> {
+    match &rule.body {
+        GrammarBody::Class { term } => match &term.body {
+            ExpressionBody::Choice(v) if term.tag.is_none() => v.branches.iter().collect(),
+            _ => vec![term],
+        },
+        GrammarBody::Union { branches } => branches.iter().map(|v| &v.branch).collect(),
+        GrammarBody::Climb { .. } => rule
+            .climb_operators()
+            .into_iter()
+            .filter(|o| matches!(o.position, ClimbPosition::Atomic | ClimbPosition::Prefix))
+            .map(|o| &o.variant.branch)
+            .collect(),
+    }
+}
+
+/// The leading literals, `'a' 'b' X` starts with `ab`
+fn literal_prefix(expr: &YggdrasilExpression) -> Option<YggdrasilText> {
+    let mut out: Option<YggdrasilText> = None;
+    let items = match &expr.body {
+        ExpressionBody::Concat(v) => v.sequence.iter().collect_vec(),
+        _ => vec![expr],
+    };
+    for item in items {
+        match (&item.body, &mut out) {
+            (ExpressionBody::Text(v), None) => out = Some(v.clone()),
+            (ExpressionBody::Text(v), Some(s)) if v.insensitive == s.insensitive => s.text.push_str(&v.text),
+            _ => break,
+        }
+    }
+    out
+}
+
+/// If `prefix` matches, `text` would never be tried
+pub(super) fn shadows(prefix: &YggdrasilText, text: &YggdrasilText) -> bool {
+    let (p, t) = (prefix.text.as_bytes(), text.text.as_bytes());
+    if p.len() > t.len() {
+        return false;
+    }
+    match (prefix.insensitive, text.insensitive) {
+        (true, _) => p.eq_ignore_ascii_case(&t[..p.len()]),
+        (false, false) => p.eq(&t[..p.len()]),
+        (false, true) => false,
+    }
+}
+
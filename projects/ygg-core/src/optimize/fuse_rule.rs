@@ -1,21 +1,291 @@
+use std::ops::RangeInclusive;
 use yggdrasil_error::Validation;
-use super::*;
+use yggdrasil_ir::{
+    data::{YggdrasilRegex, YggdrasilText},
+    rule::GrammarBody,
+};
+use super::{first_set::shadows, *};
 
+/// Fuse the branches of choices, so that fewer branches are tried one after another
+///
+/// | Before | After |
+/// | :-: | :-: |
+/// | `A B \| A C` | `A (B \| C)` |
+/// | `[a-z] \| [0-9] \| '_'` | `/[a-z0-9_]/` |
+/// | `'for' \| 'in' \| X \| 'if'` | `('for' \| 'in') \| X \| 'if'`, the literal set is matched by a trie |
+/// | `'for' \| 'foreach'` | `'for'`, the second literal is never matched |
+///
+/// The operators of `climb` are not fused.
+#[derive(Default)]
 pub struct FusionRules {}
 
 impl CodeOptimizer for FusionRules {
     fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
-        let _ = info;
-        let e = ChoiceExpression { branches: Default::default() };
-        self.fuse_choice(&e);
-        todo!()
+        let mut out = info.clone();
+        let mut errors = vec![];
+        for rule in out.rules.values_mut() {
+            if let GrammarBody::Climb { .. } = rule.body {
+                continue;
+            }
+            rule.body.for_each(|e| self.fuse_node(e, &mut errors));
+        }
+        Validation::Success { value: out, diagnostics: errors }
     }
 }
 
 impl FusionRules {
-    fn fuse_choice(&mut self, choice: &ChoiceExpression) -> Validation<ChoiceExpression> {
-        let mut errors = vec![];
-        for branch in &choice.branches {}
-        Validation::Success { value: ChoiceExpression { branches: Default::default() }, diagnostics: errors }
+    fn fuse_node(&mut self, info: &mut YggdrasilExpression, errors: &mut Vec<YggdrasilError>) {
+        match &mut info.body {
+            ExpressionBody::Choice(node) => {
+                node.branches.iter_mut().for_each(|e| self.fuse_node(e, errors));
+                node.branches = self.fuse_choice(take(&mut node.branches), errors);
+                if let [single] = node.branches.as_slice() {
+                    if single.tag.is_none() || info.tag.is_none() {
+                        let mut new = single.clone();
+                        new.tag = new.tag.or(info.tag.take());
+                        new.remark |= info.remark;
+                        *info = new;
+                    }
+                }
+            }
+            ExpressionBody::Concat(node) => node.sequence.iter_mut().for_each(|e| self.fuse_node(e, errors)),
+            ExpressionBody::Unary(node) => self.fuse_node(&mut node.base, errors),
+            // do nothing
+            _ => {}
+        }
+    }
+    fn fuse_choice(
+        &mut self,
+        branches: Vec<YggdrasilExpression>,
+        errors: &mut Vec<YggdrasilError>,
+    ) -> Vec<YggdrasilExpression> {
+        let branches = flatten_choice(branches);
+        let branches = remove_shadowed(branches);
+        let branches = self.fuse_prefix(branches, errors);
+        let branches = fuse_characters(branches);
+        group_literals(branches)
+    }
+    /// `A B | A C` => `A (B | C)`, only the adjacent branches can be fused
+    fn fuse_prefix(
+        &mut self,
+        branches: Vec<YggdrasilExpression>,
+        errors: &mut Vec<YggdrasilError>,
+    ) -> Vec<YggdrasilExpression> {
+        let mut out: Vec<YggdrasilExpression> = vec![];
+        let mut group: Vec<YggdrasilExpression> = vec![];
+        for branch in branches {
+            let same = match (group.first().and_then(concat_head), concat_head(&branch)) {
+                (Some(a), Some(b)) => same_node(a, b),
+                _ => false,
+            };
+            if !same {
+                self.flush_prefix(&mut group, &mut out, errors);
+            }
+            group.push(branch);
+        }
+        self.flush_prefix(&mut group, &mut out, errors);
+        out
+    }
+    fn flush_prefix(
+        &mut self,
+        group: &mut Vec<YggdrasilExpression>,
+        out: &mut Vec<YggdrasilExpression>,
+        errors: &mut Vec<YggdrasilError>,
+    ) {
+        if group.len() < 2 {
+            out.append(group);
+            return;
+        }
+        let mut head = None;
+        let mut rests = vec![];
+        for branch in group.drain(..) {
+            if let ExpressionBody::Concat(node) = branch.body {
+                let mut sequence = node.sequence;
+                let first = sequence.remove(0);
+                head.get_or_insert(first);
+                match sequence.len() {
+                    1 => rests.extend(sequence),
+                    _ => rests.push(ConcatExpression { sequence }.into()),
+                }
+            }
+        }
+        let mut rest: YggdrasilExpression = ChoiceExpression { branches: rests }.into();
+        self.fuse_node(&mut rest, errors);
+        out.push(ConcatExpression { sequence: head.into_iter().chain([rest]).collect() }.into())
+    }
+}
+
+/// `A | (B | C)` => `A | B | C`
+fn flatten_choice(branches: Vec<YggdrasilExpression>) -> Vec<YggdrasilExpression> {
+    let mut out = Vec::with_capacity(branches.len());
+    for branch in branches {
+        match branch.body {
+            ExpressionBody::Choice(node) if branch.tag.is_none() && !branch.remark => out.extend(flatten_choice(node.branches)),
+            _ => out.push(branch),
+        }
+    }
+    out
+}
+
+/// `'for' | 'foreach'` => `'for'`, reported by [`FirstSets`](super::FirstSets)
+fn remove_shadowed(branches: Vec<YggdrasilExpression>) -> Vec<YggdrasilExpression> {
+    let mut out: Vec<YggdrasilExpression> = Vec::with_capacity(branches.len());
+    for branch in branches {
+        if let Some(text) = as_literal(&branch) {
+            if out.iter().filter_map(as_literal).any(|p| shadows(p, text)) {
+                continue;
+            }
+        }
+        out.push(branch)
+    }
+    out
+}
+
+/// `[a-z] | [0-9] | '_'` => `/[a-z0-9_]/`
+fn fuse_characters(branches: Vec<YggdrasilExpression>) -> Vec<YggdrasilExpression> {
+    let mut out = vec![];
+    let mut run: Vec<RangeInclusive<char>> = vec![];
+    let flush = |run: &mut Vec<RangeInclusive<char>>, out: &mut Vec<YggdrasilExpression>| match run.len() {
+        0 => {}
+        1 => out.push(ExpressionBody::CharacterRange(run.remove(0)).into()),
+        _ => {
+            let mut raw = String::from("[");
+            for range in run.drain(..) {
+                escape_class(*range.start(), &mut raw);
+                if range.start() != range.end() {
+                    raw.push('-');
+                    escape_class(*range.end(), &mut raw);
+                }
+            }
+            raw.push(']');
+            out.push(YggdrasilRegex::new(raw, Default::default()).into())
+        }
+    };
+    let mut has_range = false;
+    let mut pending = vec![];
+    for branch in branches {
+        match as_character(&branch) {
+            Some(range) => {
+                has_range |= matches!(branch.body, ExpressionBody::CharacterRange(_));
+                run.push(range);
+                pending.push(branch);
+            }
+            None => {
+                // single character literals are left to the trie
+                match has_range {
+                    true => flush(&mut run, &mut out),
+                    false => out.append(&mut pending),
+                }
+                run.clear();
+                pending.clear();
+                has_range = false;
+                out.push(branch)
+            }
+        }
+    }
+    match has_range {
+        true => flush(&mut run, &mut out),
+        false => out.append(&mut pending),
+    }
+    out
+}
+
+/// `'for' | 'in' | X | 'if'` => `('for' | 'in') | X | 'if'`
+fn group_literals(branches: Vec<YggdrasilExpression>) -> Vec<YggdrasilExpression> {
+    let total = branches.len();
+    let mut out = vec![];
+    let mut run: Vec<YggdrasilExpression> = vec![];
+    for branch in branches {
+        let same = match (run.first().and_then(as_literal), as_literal(&branch)) {
+            (Some(a), Some(b)) => a.insensitive == b.insensitive,
+            (None, _) => as_literal(&branch).is_some(),
+            _ => false,
+        };
+        if !same {
+            flush_literals(&mut run, &mut out, total);
+            if as_literal(&branch).is_none() {
+                out.push(branch);
+                continue;
+            }
+        }
+        run.push(branch);
+    }
+    flush_literals(&mut run, &mut out, total);
+    out
+}
+
+fn flush_literals(run: &mut Vec<YggdrasilExpression>, out: &mut Vec<YggdrasilExpression>, total: usize) {
+    if run.len() < 2 || run.len() == total {
+        out.append(run);
     }
+    else {
+        out.push(ChoiceExpression { branches: take(run) }.into())
+    }
+}
+
+fn concat_head(info: &YggdrasilExpression) -> Option<&YggdrasilExpression> {
+    match &info.body {
+        ExpressionBody::Concat(node) if info.tag.is_none() && !info.remark && node.sequence.len() >= 2 => {
+            node.sequence.first().filter(|e| !matches!(e.body, ExpressionBody::Call(_)))
+        }
+        _ => None,
+    }
+}
+
+fn as_literal(info: &YggdrasilExpression) -> Option<&YggdrasilText> {
+    match &info.body {
+        ExpressionBody::Text(text) if info.tag.is_none() && !info.remark => Some(text),
+        _ => None,
+    }
+}
+
+fn as_character(info: &YggdrasilExpression) -> Option<RangeInclusive<char>> {
+    if info.tag.is_some() || info.remark {
+        return None;
+    }
+    match &info.body {
+        ExpressionBody::CharacterRange(range) => Some(range.clone()),
+        ExpressionBody::Text(text) if !text.insensitive => match text.text.chars().collect_vec().as_slice() {
+            [c] => Some(*c..=*c),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn escape_class(c: char, out: &mut String) {
+    if matches!(c, '\\' | ']' | '[' | '^' | '-' | '&' | '~') {
+        out.push('\\');
+    }
+    out.push(c)
+}
+
+/// Structural equality, the positions are ignored
+fn same_node(a: &YggdrasilExpression, b: &YggdrasilExpression) -> bool {
+    if a.remark != b.remark || a.tag.as_ref().map(|t| &t.text) != b.tag.as_ref().map(|t| &t.text) {
+        return false;
+    }
+    match (&a.body, &b.body) {
+        (ExpressionBody::Choice(a), ExpressionBody::Choice(b)) => same_nodes(&a.branches, &b.branches),
+        (ExpressionBody::Concat(a), ExpressionBody::Concat(b)) => same_nodes(&a.sequence, &b.sequence),
+        (ExpressionBody::Unary(a), ExpressionBody::Unary(b)) => a.operators == b.operators && same_node(&a.base, &b.base),
+        (ExpressionBody::Rule(a), ExpressionBody::Rule(b)) => {
+            a.name.text == b.name.text && a.boxed == b.boxed && a.inline == b.inline
+        }
+        (ExpressionBody::Text(a), ExpressionBody::Text(b)) => a.text == b.text && a.insensitive == b.insensitive,
+        (ExpressionBody::Regex(a), ExpressionBody::Regex(b)) => a.raw == b.raw,
+        (ExpressionBody::Ignored, ExpressionBody::Ignored) => true,
+        (ExpressionBody::Cut, ExpressionBody::Cut) => true,
+        (ExpressionBody::CharacterAny, ExpressionBody::CharacterAny) => true,
+        (ExpressionBody::CharacterRestOfLine, ExpressionBody::CharacterRestOfLine) => true,
+        (ExpressionBody::CharacterRange(a), ExpressionBody::CharacterRange(b)) => a == b,
+        (ExpressionBody::Integer(a), ExpressionBody::Integer(b)) => a == b,
+        (ExpressionBody::Boolean(a), ExpressionBody::Boolean(b)) => a == b,
+        // macros may have side effects
+        _ => false,
+    }
+}
+
+fn same_nodes(a: &[YggdrasilExpression], b: &[YggdrasilExpression]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| same_node(a, b))
 }
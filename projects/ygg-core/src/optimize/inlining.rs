@@ -1,27 +1,109 @@
 use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
 use super::*;
 
+/// Replace the references to `inline` rules and `_Rule` references with the body of the rule
+///
+/// | Grammar | Result |
+/// | :-: | :-: |
+/// | `inline class B { 'b' }`, `class A { a:B 'x' }` | `class A { a:'b' 'x' }` |
+/// | `class B { 'b' }`, `class A { _B 'x' }` | `class A { 'b' 'x' }` |
+/// | `inline class B { 'b' B? }` | not inlined, recursive |
 pub struct InlineRules {
     grammar: GrammarInfo,
     recursion_limit: usize,
+    /// rules being inlined
+    stack: Vec<String>,
+    /// rules that can't be inlined
+    refused: HashSet<String>,
 }
 
 impl Default for InlineRules {
     fn default() -> Self {
-        Self { grammar: Default::default(), recursion_limit: 1024 }
+        Self { grammar: Default::default(), recursion_limit: 1024, stack: vec![], refused: Default::default() }
     }
 }
 
 impl CodeOptimizer for InlineRules {
     fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
         self.grammar = info.clone();
+        self.refused.clear();
         let mut out = info.clone();
-        Validation::Success { value: out, diagnostics: vec![] }
+        let mut errors = vec![];
+        for rule in out.rules.values_mut() {
+            rule.body.for_each(|e| {
+                loop {
+                    self.stack.clear();
+                    let mut new = e.clone();
+                    match self.inline_node(&mut new, 0) {
+                        Ok(_) => {
+                            *e = new;
+                            break;
+                        }
+                        // the refused rule is kept as a reference in the next round
+                        Err(error) => errors.push(error),
+                    }
+                }
+            });
+        }
+        Validation::Success { value: out, diagnostics: errors }
     }
 }
 
 impl InlineRules {
     fn inline_node(&mut self, info: &mut YggdrasilExpression, depth: usize) -> Result<(), YggdrasilError> {
-        todo!()
+        match &mut info.body {
+            ExpressionBody::Choice(node) => node.branches.iter_mut().try_for_each(|e| self.inline_node(e, depth)),
+            ExpressionBody::Concat(node) => node.sequence.iter_mut().try_for_each(|e| self.inline_node(e, depth)),
+            ExpressionBody::Unary(node) => self.inline_node(&mut node.base, depth),
+            ExpressionBody::Rule(node) => {
+                let rule = match self.grammar.rules.get(&node.name.text) {
+                    Some(s) if (s.auto_inline || node.inline) && !self.refused.contains(&s.name.text) => s.clone(),
+                    _ => return Ok(()),
+                };
+                let mut body = self.inline_rule(&rule, depth)?;
+                // `tag:Rule` is kept on the body, a tagged body is wrapped
+                if let Some(tag) = info.tag.take() {
+                    if body.tag.is_some() {
+                        body = ConcatExpression { sequence: vec![body] }.into();
+                    }
+                    body.tag = Some(tag);
+                }
+                body.remark |= info.remark;
+                *info = body;
+                Ok(())
+            }
+            // do nothing
+            _ => Ok(()),
+        }
+    }
+    fn inline_rule(&mut self, rule: &GrammarRule, depth: usize) -> Result<YggdrasilExpression, YggdrasilError> {
+        let name = &rule.name.text;
+        if self.stack.contains(name) {
+            self.refused.insert(name.clone());
+            let path = self.stack.iter().skip_while(|s| s.ne(&name)).chain([name]).join(" -> ");
+            let message = format!("recursive rule `{name}` can't be inlined: {path}");
+            return Err(YggdrasilError::syntax_error(message, rule.range.clone()));
+        }
+        if depth >= self.recursion_limit {
+            self.refused.insert(name.clone());
+            let message = format!("rule `{name}` can't be inlined, recursion limit {} reached", self.recursion_limit);
+            return Err(YggdrasilError::syntax_error(message, rule.range.clone()));
+        }
+        let mut body = match &rule.body {
+            GrammarBody::Class { term } => term.clone(),
+            GrammarBody::Union { branches } => {
+                ChoiceExpression { branches: branches.iter().map(|v| v.branch.clone()).collect() }.into()
+            }
+            GrammarBody::Climb { .. } => {
+                self.refused.insert(name.clone());
+                let message = format!("climb rule `{name}` can't be inlined");
+                return Err(YggdrasilError::syntax_error(message, rule.range.clone()));
+            }
+        };
+        self.stack.push(name.clone());
+        self.inline_node(&mut body, depth + 1)?;
+        self.stack.pop();
+        Ok(body)
     }
 }
@@ -0,0 +1,204 @@
+use super::{
+    first_set::{branches, FirstSets},
+    *,
+};
+use yggdrasil_error::Validation;
+use yggdrasil_ir::nodes::YggdrasilOperator;
+
+/// Find the left-recursive cycles, mark all rules in them as [`GrammarRule::left_recursive`]
+///
+/// | Grammar | Result |
+/// | :-: | :-: |
+/// | `A { A 'x' \| 'a' }` | `A` grows from the seed `'a'` |
+/// | `A { B 'x' \| 'a' }`, `B { A 'y' \| 'b' }` | `A` and `B` grow together |
+/// | `A { A 'x' }` | error, no base case |
+/// | `A { &A 'x' \| 'a' }` | error, recursion through lookahead |
+#[derive(Default)]
+pub struct LeftRecursion {
+    first: FirstSets,
+    /// rule -> rules called at the same offset, and whether the call is inside a lookahead
+    calls: IndexMap<String, Vec<(String, bool)>>,
+}
+
+impl CodeOptimizer for LeftRecursion {
+    fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
+        let mut out = info.clone();
+        let mut errors = vec![];
+        self.first.analyze(info);
+        self.find_calls(info);
+        for cycle in self.find_cycles() {
+            for name in &cycle {
+                if let Some(rule) = out.rules.get_mut(name) {
+                    rule.left_recursive = true
+                }
+            }
+            if let Err(e) = self.check_cycle(info, &cycle) {
+                errors.push(e)
+            }
+        }
+        match errors.is_empty() {
+            true => Validation::Success { value: out, diagnostics: errors },
+            false => Validation::Failure { fatal: errors.remove(0), diagnostics: errors },
+        }
+    }
+}
+
+impl LeftRecursion {
+    fn find_calls(&mut self, info: &GrammarInfo) {
+        self.calls.clear();
+        for rule in info.rules.values() {
+            let mut calls = vec![];
+            for branch in branches(rule) {
+                self.left_calls(branch, false, &mut calls)
+            }
+            self.calls.insert(rule.name.text.clone(), calls);
+        }
+    }
+    /// All rules called before consuming any input
+    fn left_calls(&self, expr: &YggdrasilExpression, lookahead: bool, out: &mut Vec<(String, bool)>) {
+        match &expr.body {
+            ExpressionBody::Rule(r) => out.push((r.name.text.clone(), lookahead)),
+            ExpressionBody::Choice(v) => v.branches.iter().for_each(|e| self.left_calls(e, lookahead, out)),
+            ExpressionBody::Concat(v) => {
+                for item in &v.sequence {
+                    self.left_calls(item, lookahead, out);
+                    if !self.first.is_nullable(item) {
+                        break;
+                    }
+                }
+            }
+            ExpressionBody::Unary(v) => {
+                let lookahead = lookahead
+                    || v.operators.iter().any(|o| matches!(o, YggdrasilOperator::Positive | YggdrasilOperator::Negative));
+                self.left_calls(&v.base, lookahead, out)
+            }
+            _ => {}
+        }
+    }
+    /// Tarjan's strongly connected components, only the ones forming a cycle are returned
+    fn find_cycles(&self) -> Vec<Vec<String>> {
+        let mut tarjan = Tarjan::default();
+        for name in self.calls.keys() {
+            if !tarjan.index.contains_key(name) {
+                tarjan.visit(name, &self.calls)
+            }
+        }
+        let mut out = vec![];
+        for scc in tarjan.components {
+            let is_cycle = match scc.as_slice() {
+                [single] => self.callees(single).any(|(callee, _)| callee.eq(single)),
+                _ => true,
+            };
+            if is_cycle {
+                out.push(scc)
+            }
+        }
+        out
+    }
+    fn callees<'a>(&'a self, name: &str) -> impl Iterator<Item = &'a (String, bool)> + 'a {
+        self.calls.get(name).into_iter().flatten()
+    }
+    fn check_cycle(&self, info: &GrammarInfo, cycle: &[String]) -> Result<(), YggdrasilError> {
+        let head = &cycle[0];
+        let range = info.rules.get(head).map(|r| r.range.clone()).unwrap_or_default();
+        for name in cycle {
+            if self.callees(name).any(|(callee, lookahead)| *lookahead && cycle.contains(callee)) {
+                let message = format!("left recursion through lookahead is not supported: {}", self.cycle_path(cycle));
+                return Err(YggdrasilError::syntax_error(message, range));
+            }
+        }
+        let has_seed = cycle.iter().filter_map(|name| info.rules.get(name)).any(|rule| {
+            branches(rule).iter().any(|branch| {
+                let mut calls = vec![];
+                self.left_calls(branch, false, &mut calls);
+                calls.iter().all(|(callee, _)| !cycle.contains(callee))
+            })
+        });
+        if !has_seed {
+            let message = format!("left recursion without a base case: {}", self.cycle_path(cycle));
+            return Err(YggdrasilError::syntax_error(message, range));
+        }
+        Ok(())
+    }
+    /// `A -> B -> A`
+    fn cycle_path(&self, cycle: &[String]) -> String {
+        let head = &cycle[0];
+        let mut path = vec![head.as_str()];
+        let mut visited = HashSet::new();
+        if !self.walk_path(head, head, cycle, &mut visited, &mut path) {
+            path.push(head);
+        }
+        path.join(" -> ")
+    }
+    fn walk_path<'a>(
+        &'a self,
+        head: &str,
+        current: &str,
+        cycle: &[String],
+        visited: &mut HashSet<&'a str>,
+        path: &mut Vec<&'a str>,
+    ) -> bool {
+        for (callee, _) in self.callees(current) {
+            if callee.eq(head) {
+                path.push(callee);
+                return true;
+            }
+            if cycle.contains(callee) && visited.insert(callee) {
+                path.push(callee);
+                if self.walk_path(head, callee, cycle, visited, path) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+}
+
+#[derive(Default)]
+struct Tarjan {
+    counter: usize,
+    index: IndexMap<String, usize>,
+    low: IndexMap<String, usize>,
+    stack: Vec<String>,
+    on_stack: HashSet<String>,
+    components: Vec<Vec<String>>,
+}
+
+impl Tarjan {
+    fn visit(&mut self, name: &str, calls: &IndexMap<String, Vec<(String, bool)>>) {
+        self.index.insert(name.to_string(), self.counter);
+        self.low.insert(name.to_string(), self.counter);
+        self.counter += 1;
+        self.stack.push(name.to_string());
+        self.on_stack.insert(name.to_string());
+        for (callee, _) in calls.get(name).into_iter().flatten() {
+            // calls to undefined rules are reported elsewhere
+            if !calls.contains_key(callee) {
+                continue;
+            }
+            if !self.index.contains_key(callee) {
+                self.visit(callee, calls);
+                let low = self.low[name].min(self.low[callee]);
+                self.low.insert(name.to_string(), low);
+            }
+            else if self.on_stack.contains(callee) {
+                let low = self.low[name].min(self.index[callee]);
+                self.low.insert(name.to_string(), low);
+            }
+        }
+        if self.low[name] == self.index[name] {
+            let mut component = vec![];
+            while let Some(top) = self.stack.pop() {
+                self.on_stack.remove(&top);
+                let done = top.eq(name);
+                component.push(top);
+                if done {
+                    break;
+                }
+            }
+            component.reverse();
+            self.components.push(component);
+        }
+    }
+}
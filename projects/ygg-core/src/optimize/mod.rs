@@ -8,16 +8,29 @@ use yggdrasil_ir::{
     rule::{FunctionRule, GrammarAtomic, GrammarRule},
     IndexMap,
 };
+mod box_fields;
 mod dead_code;
 mod emit_function;
+mod first_set;
 mod fuse_rule;
 mod inlining;
 mod insert_ignore;
+mod left_recursion;
 mod remark_tag;
+mod resolve;
 
 mod refine;
 
 pub use self::{
-    dead_code::DeadCodeEliminator, emit_function::EmitFunction, fuse_rule::FusionRules, inlining::InlineRules,
-    insert_ignore::InsertIgnore, refine::RefineRules, remark_tag::RemarkTags,
+    box_fields::BoxFields,
+    dead_code::DeadCodeEliminator,
+    emit_function::EmitFunction,
+    first_set::{FirstSet, FirstSets},
+    fuse_rule::FusionRules,
+    inlining::InlineRules,
+    insert_ignore::InsertIgnore,
+    left_recursion::LeftRecursion,
+    refine::RefineRules,
+    remark_tag::RemarkTags,
+    resolve::ResolveNames,
 };
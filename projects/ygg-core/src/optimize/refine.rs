@@ -23,15 +23,14 @@ impl CodeOptimizer for RefineRules {
                     Ok(_) => {}
                     Err(e) => errors.push(e),
                 },
-                GrammarBody::Union { branches } => {
-                    for variant in branches.iter_mut() {
+                GrammarBody::Union { branches: variants } | GrammarBody::Climb { priority: variants } => {
+                    for variant in variants.iter_mut() {
                         match self.refine_node(&mut variant.branch) {
                             Ok(_) => {}
                             Err(e) => errors.push(e),
                         }
                     }
                 }
-                GrammarBody::Climb { .. } => {}
             }
         }
         Validation::Success { value: out, diagnostics: errors }
@@ -21,7 +21,13 @@ impl CodeOptimizer for RemarkTags {
                     }
                     self.remark_union_root(branches, rule_name);
                 }
-                GrammarBody::Climb { .. } => {}
+                GrammarBody::Climb { priority } => {
+                    for variant in priority.iter_mut() {
+                        self.remark_operands(&mut variant.branch, rule_name);
+                        self.remark(&mut variant.branch, rule.captures.auto)
+                    }
+                    self.remark_climb_root(priority, rule_name);
+                }
             }
         }
 
@@ -78,4 +84,37 @@ impl RemarkTags {
             variant.unmark()
         }
     }
+    /// `Self '+' Self` -> `lhs:Self '+' rhs:Self`
+    fn remark_operands(&self, expr: &mut YggdrasilExpression, rule: &str) {
+        let sequence = match &mut expr.body {
+            ExpressionBody::Concat(v) if expr.tag.is_none() => &mut v.sequence,
+            _ => return,
+        };
+        let is_operand = |e: &YggdrasilExpression| e.tag.is_none() && e.as_rule().map_or(false, |r| r.name.text.eq(rule));
+        if let Some(head) = sequence.first_mut().filter(|e| is_operand(e)) {
+            head.tag = Some(YggdrasilIdentifier { text: "lhs".to_string(), range: Default::default() })
+        }
+        if let Some(last) = sequence.last_mut().filter(|e| is_operand(e)) {
+            last.tag = Some(YggdrasilIdentifier { text: "rhs".to_string(), range: Default::default() })
+        }
+    }
+    fn remark_climb_root(&self, expr: &mut [YggdrasilVariant], rule: &str) {
+        for (index, variant) in expr.iter_mut().enumerate() {
+            match variant.tag {
+                Some(_) => {}
+                None => match &variant.branch.body {
+                    ExpressionBody::Rule(r) => variant
+                        .remark(YggdrasilIdentifier { text: r.name.text.to_case(Case::Pascal), range: r.name.range.clone() }),
+                    _ => variant.remark(YggdrasilIdentifier {
+                        text: format!("{rule}{index}").to_case(Case::Pascal),
+                        range: Default::default(),
+                    }),
+                },
+            }
+            // operators are wrapped as a node later, only the atom need a tag to be captured
+            if variant.branch.is_rule() {
+                variant.unmark()
+            }
+        }
+    }
 }
@@ -0,0 +1,136 @@
+use super::*;
+use convert_case::{Case, Casing};
+use std::collections::BTreeMap;
+use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::{GrammarBody, YggdrasilIdentifier};
+
+/// Check that all rule references are defined, and that the tags of a rule are still distinct in the ast
+///
+/// Must run before the other optimizers, the generated code is broken if any name can't be resolved.
+#[derive(Default)]
+pub struct ResolveNames {
+    errors: Vec<YggdrasilError>,
+}
+
+impl CodeOptimizer for ResolveNames {
+    fn optimize(&mut self, info: &GrammarInfo) -> Validation<GrammarInfo> {
+        self.errors.clear();
+        let names = info.rules.keys().map(|s| s.as_str()).collect_vec();
+        for rule in info.rules.values() {
+            match &rule.body {
+                GrammarBody::Class { term } => self.resolve(term, &names),
+                GrammarBody::Union { branches } | GrammarBody::Climb { priority: branches } => {
+                    branches.iter().for_each(|v| self.resolve(&v.branch, &names))
+                }
+            }
+            rule.recover.iter().for_each(|e| self.resolve(e, &names));
+        }
+        for function in info.functions.values() {
+            let mut scope = names.clone();
+            scope.extend(function.parameters.iter().map(|p| p.text.as_str()));
+            self.resolve(&function.body, &scope);
+        }
+        // the fields are named by the auto tags too
+        if let Validation::Success { value, .. } = RemarkTags::default().optimize(info) {
+            value.rules.values().for_each(|rule| self.check_tags(rule));
+        }
+        let mut errors = take(&mut self.errors);
+        match errors.is_empty() {
+            true => Validation::Success { value: info.clone(), diagnostics: errors },
+            false => {
+                let fatal = errors.remove(0);
+                Validation::Failure { fatal, diagnostics: errors }
+            }
+        }
+    }
+}
+
+impl ResolveNames {
+    fn resolve(&mut self, info: &YggdrasilExpression, names: &[&str]) {
+        match &info.body {
+            ExpressionBody::Choice(node) => node.branches.iter().for_each(|e| self.resolve(e, names)),
+            ExpressionBody::Concat(node) => node.sequence.iter().for_each(|e| self.resolve(e, names)),
+            ExpressionBody::Unary(node) => self.resolve(&node.base, names),
+            ExpressionBody::Call(node) => node.arguments.iter().for_each(|a| self.resolve(&a.value, names)),
+            ExpressionBody::Rule(node) if !names.contains(&node.name.text.as_str()) => {
+                let message = match suggest(&node.name.text, names) {
+                    Some(s) => format!("rule `{}` is not defined, did you mean `{s}`?", node.name.text),
+                    None => format!("rule `{}` is not defined", node.name.text),
+                };
+                self.errors.push(YggdrasilError::syntax_error(message, node.name.range.clone()))
+            }
+            _ => {}
+        }
+    }
+    fn check_tags(&mut self, rule: &GrammarRule) {
+        match &rule.body {
+            GrammarBody::Class { term } => {
+                let mut tags = vec![];
+                find_tags(term, &mut tags);
+                self.check_collision(rule, &tags, "field");
+            }
+            GrammarBody::Union { branches } | GrammarBody::Climb { priority: branches } => {
+                let variants = branches.iter().filter_map(|v| v.tag.as_ref()).collect_vec();
+                self.check_collision(rule, &variants, "variant");
+                for variant in branches {
+                    let mut tags = vec![];
+                    find_tags(&variant.branch, &mut tags);
+                    self.check_collision(rule, &tags, "field");
+                }
+            }
+        }
+    }
+    /// The same tag is the same field, different tags with the same snake case name can't be told apart
+    fn check_collision(&mut self, rule: &GrammarRule, tags: &[&YggdrasilIdentifier], kind: &str) {
+        let mut seen: BTreeMap<String, &YggdrasilIdentifier> = BTreeMap::new();
+        for tag in tags {
+            match seen.get(&tag.text.to_case(Case::Snake)) {
+                Some(old) if old.text.ne(&tag.text) => {
+                    let message = format!("{kind} `{}` of `{}` collides with `{}`", tag.text, rule.name.text, old.text);
+                    let error = YggdrasilError::syntax_error(message, tag.range.clone());
+                    self.errors.push(error.with_label(format!("`{}` is defined here", old.text), old.range.clone()))
+                }
+                Some(_) => {}
+                None => {
+                    seen.insert(tag.text.to_case(Case::Snake), tag);
+                }
+            }
+        }
+    }
+}
+
+fn find_tags<'i>(info: &'i YggdrasilExpression, out: &mut Vec<&'i YggdrasilIdentifier>) {
+    out.extend(info.tag.as_ref());
+    match &info.body {
+        ExpressionBody::Choice(node) => node.branches.iter().for_each(|e| find_tags(e, out)),
+        ExpressionBody::Concat(node) => node.sequence.iter().for_each(|e| find_tags(e, out)),
+        ExpressionBody::Unary(node) => find_tags(&node.base, out),
+        _ => {}
+    }
+}
+
+/// The closest name within a third of the length, ignoring case
+fn suggest<'i>(name: &str, names: &[&'i str]) -> Option<&'i str> {
+    let limit = name.chars().count().div_ceil(3);
+    names
+        .iter()
+        .map(|other| (edit_distance(&name.to_lowercase(), &other.to_lowercase()), *other))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, other)| other)
+}
+
+fn edit_distance(lhs: &str, rhs: &str) -> usize {
+    let rhs = rhs.chars().collect_vec();
+    let mut row = (0..=rhs.len()).collect_vec();
+    for (i, a) in lhs.chars().enumerate() {
+        let mut last = row[0];
+        row[0] = i + 1;
+        for (j, b) in rhs.iter().enumerate() {
+            let next = row[j + 1];
+            row[j + 1] = if a.eq(b) { last } else { 1 + last.min(row[j]).min(next) };
+            last = next;
+        }
+    }
+    row[rhs.len()]
+}
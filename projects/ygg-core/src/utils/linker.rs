@@ -0,0 +1,342 @@
+use crate::optimize::RemarkTags;
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env::current_dir,
+    fs::read_to_string,
+    mem::take,
+    ops::Range,
+    path::Path,
+};
+use yggdrasil_error::{Validation, YggdrasilError};
+use yggdrasil_ir::{
+    grammar::GrammarInfo,
+    nodes::{ExpressionBody, YggdrasilExpression},
+    rule::{GrammarBody, GrammarRule, YggdrasilField},
+    traits::CodeOptimizer,
+    Url,
+};
+
+/// Load the base grammar and the files imported by `using`, then merge their rules
+///
+/// The rules that the imported rules depend on are merged too, with their original names.
+///
+/// `grammar Json5 : Json` inherits all rules of `json.ygg` in the same folder,
+/// a rule with the same name overrides the inherited rule, and `extend union` appends branches to it.
+#[derive(Default)]
+pub struct GrammarLinker {
+    /// Linked grammars by url
+    linked: BTreeMap<Url, GrammarInfo>,
+    /// Rules defined or inherited by the linked grammars, the rules imported by `using` are not imported again
+    defined: BTreeMap<Url, BTreeSet<String>>,
+    /// Files being linked, an import cycle is found if a file is linked again
+    stack: Vec<Url>,
+    /// Errors in the linked files, and overrides that change the ast
+    errors: Vec<YggdrasilError>,
+}
+
+impl GrammarLinker {
+    /// Parse and link the grammar file
+    pub fn link_file(&mut self, path: &Path) -> Validation<GrammarInfo> {
+        let result = match path.canonicalize().map(Url::from_file_path) {
+            Ok(Ok(url)) => self.link_url(&url),
+            Ok(Err(_)) => Err(YggdrasilError::runtime_error(format!("invalid grammar path `{}`", path.display()))),
+            Err(e) => Err(e.into()),
+        };
+        self.finish(result)
+    }
+    /// Link a parsed grammar, the grammar is linked as the file of its url
+    pub fn link(&mut self, info: GrammarInfo) -> Validation<GrammarInfo> {
+        let result = self.link_info(info);
+        self.finish(result)
+    }
+    fn finish(&mut self, result: Result<GrammarInfo, YggdrasilError>) -> Validation<GrammarInfo> {
+        let diagnostics = take(&mut self.errors);
+        match result {
+            Ok(value) => Validation::Success { value, diagnostics },
+            Err(fatal) => Validation::Failure { fatal, diagnostics },
+        }
+    }
+    fn link_info(&mut self, mut info: GrammarInfo) -> Result<GrammarInfo, YggdrasilError> {
+        self.stack.extend(info.url.clone());
+        let result = self.inherit(&mut info).and_then(|_| {
+            if let Some(url) = &info.url {
+                self.defined.insert(url.clone(), info.rules.keys().cloned().collect());
+            }
+            self.merge_imports(&mut info)
+        });
+        self.stack.truncate(self.stack.len() - info.url.iter().count());
+        result.map(|_| info)
+    }
+    fn link_url(&mut self, url: &Url) -> Result<GrammarInfo, YggdrasilError> {
+        if let Some(s) = self.linked.get(url) {
+            return Ok(s.clone());
+        }
+        let path = match url.to_file_path() {
+            Ok(o) => o,
+            Err(_) => return Err(YggdrasilError::runtime_error(format!("grammar `{url}` is not a file"))),
+        };
+        let info = match GrammarInfo::from_source(&read_to_string(path)?, Some(url.clone())) {
+            Validation::Success { value, diagnostics } => {
+                self.errors.extend(diagnostics);
+                value
+            }
+            Validation::Failure { fatal, diagnostics } => {
+                self.errors.extend(diagnostics);
+                return Err(fatal);
+            }
+        };
+        let info = self.link_info(info)?;
+        self.linked.insert(url.clone(), info.clone());
+        Ok(info)
+    }
+    fn check_cycle(&self, url: &Url, range: Range<usize>) -> Result<(), YggdrasilError> {
+        match self.stack.iter().position(|u| url.eq(u)) {
+            Some(index) => {
+                let path = self.stack[index..].iter().chain([url]).map(file_name).join(" -> ");
+                Err(YggdrasilError::syntax_error(format!("import cycle: {path}"), range))
+            }
+            None => Ok(()),
+        }
+    }
+    fn inherit(&mut self, info: &mut GrammarInfo) -> Result<(), YggdrasilError> {
+        let base = match &info.base {
+            Some(s) => s.clone(),
+            None => return Ok(()),
+        };
+        let file = format!("{}.ygg", base.text.to_case(Case::Snake));
+        let url = match &info.url {
+            Some(s) => s.join(&file).ok(),
+            None => Url::from_file_path(current_dir()?.join(&file)).ok(),
+        };
+        let url = match url {
+            Some(s) => s,
+            None => return Err(YggdrasilError::syntax_error(format!("invalid base grammar `{file}`"), base.range)),
+        };
+        self.check_cycle(&url, base.range.clone())?;
+        let parent = self.link_url(&url)?;
+        if parent.name.text != base.text {
+            let message = format!("`{file}` defines grammar `{}` instead of `{}`", parent.name.text, base.text);
+            return Err(YggdrasilError::syntax_error(message, base.range));
+        }
+        let mut rules = parent.rules.clone();
+        for name in &info.removed {
+            if rules.shift_remove(&name.text).is_none() {
+                let message = format!("`{}` is not defined in base grammar `{}`", name.text, base.text);
+                return Err(YggdrasilError::syntax_error(message, name.range.clone()));
+            }
+        }
+        // overrides keep the position of the inherited rule
+        for (name, rule) in take(&mut info.rules) {
+            match rules.get_mut(&name) {
+                Some(old) if rule.extend => extend_rule(old, rule)?,
+                Some(old) => {
+                    self.check_shape(old, &rule);
+                    *old = rule;
+                }
+                None if rule.extend => {
+                    let message = format!("`{name}` is not defined in base grammar `{}`", base.text);
+                    return Err(YggdrasilError::syntax_error(message, rule.name.range.clone()));
+                }
+                None => {
+                    rules.insert(name, rule);
+                }
+            }
+        }
+        info.rules = rules;
+        for (name, function) in &parent.functions {
+            info.functions.entry(name.clone()).or_insert_with(|| function.clone());
+        }
+        for (name, set) in &parent.token_sets {
+            info.token_sets.entry(name.clone()).or_insert_with(|| set.clone());
+        }
+        if info.extensions.is_empty() {
+            info.extensions = parent.extensions.clone();
+        }
+        Ok(())
+    }
+    /// An override is allowed to change the ast, but the code using the base ast may break
+    fn check_shape(&mut self, old: &GrammarRule, new: &GrammarRule) {
+        let (old, new) = (remark(old), remark(new));
+        let name = &new.name.text;
+        let mut warn = |message: String| self.errors.push(YggdrasilError::syntax_error(message, new.range.clone()));
+        match (&old.body, &new.body) {
+            (GrammarBody::Class { .. }, GrammarBody::Class { .. }) => {
+                let fields = new.class_fields().fields;
+                for (key, field) in old.class_fields().fields {
+                    match fields.get(&key) {
+                        Some(s) if field_shape(s) == field_shape(&field) => {}
+                        Some(s) => warn(format!(
+                            "field `{key}` of `{name}` changes from `{}` to `{}`",
+                            field_shape(&field),
+                            field_shape(s)
+                        )),
+                        None => warn(format!("field `{key}` of `{name}` is removed by the override")),
+                    }
+                }
+            }
+            (GrammarBody::Union { branches: base }, GrammarBody::Union { branches })
+            | (GrammarBody::Climb { priority: base }, GrammarBody::Climb { priority: branches }) => {
+                let tags: BTreeSet<_> = branches.iter().filter_map(|v| v.tag.as_ref()).map(|v| &v.text).collect();
+                for tag in base.iter().filter_map(|v| v.tag.as_ref()).filter(|v| !tags.contains(&v.text)) {
+                    warn(format!("variant `{}` of `{name}` is removed by the override", tag.text))
+                }
+            }
+            _ => warn(format!("`{name}` is overridden by a {}, the ast node changes", body_kind(&new.body))),
+        }
+    }
+    fn merge_imports(&mut self, info: &mut GrammarInfo) -> Result<(), YggdrasilError> {
+        for (url, symbols) in info.imports.clone() {
+            let range = symbols.first().map(|s| s.range.clone()).unwrap_or_default();
+            self.check_cycle(&url, range.clone())?;
+            let other = self.link_url(&url)?;
+            let file = file_name(&url);
+            let defined = self.defined.get(&url).cloned().unwrap_or_default();
+            // rename the imported rules to the alias
+            let mut alias = BTreeMap::new();
+            for symbol in &symbols {
+                if !defined.contains(&symbol.name) {
+                    let message = format!("`{}` is not defined in `{file}`", symbol.name);
+                    return Err(YggdrasilError::syntax_error(message, symbol.range.clone()));
+                }
+                alias.insert(symbol.name.clone(), symbol.alias.clone());
+            }
+            if symbols.is_empty() {
+                alias.extend(defined.iter().map(|name| (name.clone(), name.clone())));
+            }
+            let needed = depends_on(&other, &alias);
+            for rule in other.rules.values().filter(|r| needed.contains(&r.name.text)) {
+                let mut rule = rule.clone();
+                rename_rule(&mut rule, &alias);
+                match info.rules.get(&rule.name.text) {
+                    Some(old) if old.eq(&rule) => continue,
+                    Some(_) => {
+                        let range =
+                            symbols.iter().find(|s| s.alias.eq(&rule.name.text)).map_or(range.clone(), |s| s.range.clone());
+                        let message = format!("rule `{}` imported from `{file}` clashes with an existing rule", rule.name.text);
+                        return Err(YggdrasilError::syntax_error(message, range));
+                    }
+                    None => {}
+                }
+                info.rules.insert(rule.name.text.clone(), rule);
+            }
+            // the macros are expanded after linking
+            for (name, function) in &other.functions {
+                info.functions.entry(name.clone()).or_insert_with(|| function.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// All rules needed by the imported rules, including themselves
+fn depends_on(info: &GrammarInfo, imported: &BTreeMap<String, String>) -> BTreeSet<String> {
+    let mut rules = BTreeSet::new();
+    let mut calls = BTreeSet::new();
+    let mut names = imported.keys().cloned().collect_vec();
+    let mut pending = vec![];
+    loop {
+        // rules and macros are visited once, so that recursive macros end
+        while let Some(name) = names.pop() {
+            if let Some(rule) = info.rules.get(&name).filter(|_| rules.insert(name.clone())) {
+                rule.body.clone().for_each(|e| pending.push(e.clone()));
+                pending.extend(rule.recover.iter().cloned());
+            }
+            if let Some(function) = info.functions.get(&name).filter(|_| calls.insert(name.clone())) {
+                pending.push(function.body.clone());
+            }
+        }
+        match pending.pop() {
+            Some(expr) => names.extend(find_references(&expr, &mut pending)),
+            None => break,
+        }
+    }
+    rules
+}
+
+/// Returns the referenced names, the nested expressions are pushed to the queue
+fn find_references(expr: &YggdrasilExpression, queue: &mut Vec<YggdrasilExpression>) -> Vec<String> {
+    match &expr.body {
+        ExpressionBody::Choice(node) => queue.extend(node.branches.iter().cloned()),
+        ExpressionBody::Concat(node) => queue.extend(node.sequence.iter().cloned()),
+        ExpressionBody::Unary(node) => queue.push(node.base.as_ref().clone()),
+        ExpressionBody::Call(node) => {
+            queue.extend(node.arguments.iter().map(|a| a.value.clone()));
+            return vec![node.name.to_string()];
+        }
+        ExpressionBody::Rule(node) => return vec![node.name.text.clone()],
+        _ => {}
+    }
+    vec![]
+}
+
+fn rename_rule(rule: &mut GrammarRule, alias: &BTreeMap<String, String>) {
+    if let Some(s) = alias.get(&rule.name.text) {
+        rule.name.text = s.clone();
+    }
+    rule.body.for_each(|e| rename(e, alias));
+    rule.recover.iter_mut().for_each(|e| rename(e, alias));
+}
+
+fn rename(expr: &mut YggdrasilExpression, alias: &BTreeMap<String, String>) {
+    match &mut expr.body {
+        ExpressionBody::Choice(node) => node.branches.iter_mut().for_each(|e| rename(e, alias)),
+        ExpressionBody::Concat(node) => node.sequence.iter_mut().for_each(|e| rename(e, alias)),
+        ExpressionBody::Unary(node) => rename(&mut node.base, alias),
+        ExpressionBody::Call(node) => node.arguments.iter_mut().for_each(|a| rename(&mut a.value, alias)),
+        ExpressionBody::Rule(node) => {
+            if let Some(s) = alias.get(&node.name.text) {
+                node.name.text = s.clone();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn extend_rule(old: &mut GrammarRule, new: GrammarRule) -> Result<(), YggdrasilError> {
+    let kind = body_kind(&old.body);
+    match (&mut old.body, new.body) {
+        (GrammarBody::Union { branches }, GrammarBody::Union { branches: extra }) => branches.extend(extra),
+        (GrammarBody::Climb { priority }, GrammarBody::Climb { priority: extra }) => priority.extend(extra),
+        _ => {
+            let message = format!("`extend` only appends branches to a union or climb, `{}` is a {kind}", old.name.text);
+            return Err(YggdrasilError::syntax_error(message, new.range));
+        }
+    }
+    Ok(())
+}
+
+/// The tags inserted by [RemarkTags] decide the fields of the ast
+fn remark(rule: &GrammarRule) -> GrammarRule {
+    let mut info = GrammarInfo::default();
+    info.insert(rule.clone());
+    match RemarkTags::default().optimize(&info) {
+        Validation::Success { value, .. } => value.rules.into_values().next().unwrap_or_else(|| rule.clone()),
+        Validation::Failure { .. } => rule.clone(),
+    }
+}
+
+fn body_kind(body: &GrammarBody) -> &'static str {
+    match body {
+        GrammarBody::Class { .. } => "class",
+        GrammarBody::Union { .. } => "union",
+        GrammarBody::Climb { .. } => "climb",
+    }
+}
+
+fn field_shape(field: &YggdrasilField) -> String {
+    if field.count.is_one() {
+        field.rhs.clone()
+    }
+    else if field.count.is_optional() {
+        format!("Option<{}>", field.rhs)
+    }
+    else {
+        format!("Vec<{}>", field.rhs)
+    }
+}
+
+fn file_name(url: &Url) -> &str {
+    url.path_segments().and_then(|mut s| s.next_back()).unwrap_or(url.as_str())
+}
@@ -1,8 +1,14 @@
-use crate::optimize::{InsertIgnore, RefineRules, RemarkTags};
-use std::str::FromStr;
+use crate::optimize::{
+    BoxFields, DeadCodeEliminator, EmitFunction, FirstSets, FusionRules, InlineRules, InsertIgnore, LeftRecursion,
+    RefineRules, RemarkTags, ResolveNames,
+};
+use std::{path::Path, str::FromStr};
 use yggdrasil_error::{Validate, Validation, YggdrasilError};
 use yggdrasil_ir::{grammar::GrammarInfo, traits::CodeOptimizer};
 
+mod linker;
+
+pub use self::linker::GrammarLinker;
 
 pub fn parse_grammar_raw(grammar: &str) -> Result<GrammarInfo, YggdrasilError> {
     Ok(GrammarInfo::from_str(grammar)?)
@@ -10,9 +16,30 @@ pub fn parse_grammar_raw(grammar: &str) -> Result<GrammarInfo, YggdrasilError> {
 
 pub fn parse_grammar(grammar: &str) -> Validation<GrammarInfo> {
     let mut errors = vec![];
-    let mut info = GrammarInfo::from_str(grammar).validate(&mut errors)?;
+    let info = GrammarInfo::from_source(grammar, None).validate(&mut errors)?;
+    let info = GrammarLinker::default().link(info).validate(&mut errors)?;
+    optimize_grammar(info, errors)
+}
+
+/// Parse the grammar file and the files imported by `using`
+pub fn load_grammar<P: AsRef<Path>>(path: P) -> Validation<GrammarInfo> {
+    let mut errors = vec![];
+    let info = GrammarLinker::default().link_file(path.as_ref()).validate(&mut errors)?;
+    optimize_grammar(info, errors)
+}
+
+/// Run all optimization passes on a linked grammar, the `errors` are the diagnostics collected before
+pub fn optimize_grammar(mut info: GrammarInfo, mut errors: Vec<YggdrasilError>) -> Validation<GrammarInfo> {
+    info = ResolveNames::default().optimize(&info).validate(&mut errors)?;
+    info = EmitFunction::default().optimize(&info).validate(&mut errors)?;
     info = RefineRules::default().optimize(&info).validate(&mut errors)?;
+    info = FirstSets::default().optimize(&info).validate(&mut errors)?;
     info = InsertIgnore::default().optimize(&info).validate(&mut errors)?;
+    info = InlineRules::default().optimize(&info).validate(&mut errors)?;
+    info = FusionRules::default().optimize(&info).validate(&mut errors)?;
+    info = DeadCodeEliminator::default().optimize(&info).validate(&mut errors)?;
+    info = LeftRecursion::default().optimize(&info).validate(&mut errors)?;
     info = RemarkTags::default().optimize(&info).validate(&mut errors)?;
+    info = BoxFields::default().optimize(&info).validate(&mut errors)?;
     Validation::Success { value: info, diagnostics: errors }
 }
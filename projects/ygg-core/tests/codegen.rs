@@ -0,0 +1,302 @@
+use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
+use yggdrasil_shared::{
+    codegen::{RustCodegen, RustModule, VscodeCodegen},
+    parse_grammar, GrammarInfo, YggdrasilExpression,
+};
+
+/// Lower the grammar to the rust parser
+fn lower(grammar: &str) -> RustModule {
+    generate(optimized(grammar))
+}
+
+fn optimized(grammar: &str) -> GrammarInfo {
+    match parse_grammar(grammar) {
+        Validation::Success { value, .. } => value,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+fn generate(info: GrammarInfo) -> RustModule {
+    match info.generate(RustCodegen::default()) {
+        Validation::Success { value, .. } => value,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+fn assert_lowered(code: &str, expected: &[&str]) {
+    for part in expected {
+        assert!(code.contains(part), "`{part}` is not found in:\n{code}")
+    }
+}
+
+#[test]
+fn lower_climb() {
+    let cst = lower(
+        r#"
+grammar Demo {}
+
+entry class Root {
+    Expr
+}
+climb Expr {
+    | Atom                 #Atom
+    | x:Expr '+' y:Expr    #Add
+    | x:Expr '*' y:Expr    #Mul
+    | '-' x:Expr           #Neg
+}
+class Atom {
+    [a-z]
+}
+"#,
+    )
+    .cst;
+    assert_lowered(
+        &cst,
+        &[
+            "fn climb_expr(state: Input, min: u32) -> Output",
+            "climb_expr(s, 0)",
+            // prefix operator parses the operand with the lowest binding power
+            "s.rule(DemoRule::Expr, |s| climb_expr(s, 1))",
+            // earlier branches bind tighter, the right operand climbs above the operator
+            r#"if 3 >= min { s.climb(start, DemoRule::Expr, "x", "add""#,
+            "climb_expr(s, 4)",
+            r#"if 2 >= min { s.climb(start, DemoRule::Expr, "x", "mul""#,
+            "climb_expr(s, 3)",
+        ],
+    );
+}
+
+#[test]
+fn lower_recover() {
+    let module = lower(
+        r#"
+grammar Demo {}
+
+entry class Program {
+    Statement*
+}
+@recover(';', Statement)
+class Statement {
+    Error '=' Error ';'
+}
+class Error {
+    [a-z]
+}
+"#,
+    );
+    // the skipped text doesn't collide with the user rule `Error`
+    assert_lowered(&module.main, &["    Error,", "    IgnoreError,"]);
+    assert_lowered(
+        &module.cst,
+        &[
+            "DemoRule::Error => parse_error(state),",
+            "DemoRule::IgnoreError => unreachable!(),",
+            "state.recover(\n        DemoRule::IgnoreError,\n        |s| s.rule(DemoRule::Statement, |s| {",
+            // literals are consumed, rules are only looked ahead
+            "|s| Err(s).or_else(|s|s.match_string(\";\", false)).or_else(|s|s.lookahead(true, |s|parse_statement(s))),",
+        ],
+    );
+}
+
+#[test]
+fn lower_builtins() {
+    let mut info = optimized(
+        r#"
+grammar Demo {}
+
+entry class Version {
+    'v' Two Digit Comment Never
+}
+class Two {
+    'placeholder'
+}
+class Digit {
+    ASCII_DIGIT
+}
+class Comment {
+    '#' ROL
+}
+class Never {
+    false
+}
+"#,
+    );
+    // integer literals are not parsed by the bootstrap parser yet
+    info.rules["Two"].body = GrammarBody::Class { term: YggdrasilExpression::integer(2) };
+    assert_lowered(
+        &generate(info).cst,
+        &[
+            "s.match_string(\"2\", false)",
+            // `match_range` is inclusive
+            "s.match_range('0'..'9')",
+            "and_then(|s|builtin_rest_of_line(s))",
+            "state.rule(DemoRule::Never, |s| {\n        Err(s)\n    })",
+        ],
+    );
+}
+
+#[test]
+fn lower_label() {
+    let cst = lower(
+        r#"
+grammar Demo {}
+
+entry class Program {
+    Statement*
+}
+@label("statement")
+@error("expected a statement")
+class Statement {
+    'let' Name '=' @label("expression", Name) ';'
+}
+class Name {
+    [a-z]+
+}
+"#,
+    )
+    .cst;
+    assert_lowered(
+        &cst,
+        &[
+            // the annotations wrap the whole rule
+            concat!(
+                "state.label(\"statement\", |state|state.error(\"expected a statement\", |state|\n",
+                "    state.rule(DemoRule::Statement, |s| {",
+            ),
+            "s.label(\"expression\",|s|parse_name(s).and_then(|s| s.tag_node(\"name\")))",
+        ],
+    );
+}
+
+#[test]
+fn lower_cut() {
+    let cst = lower(
+        r#"
+grammar Demo {}
+
+entry class Statement {
+    'let' @cut() Name ';'
+}
+class Name {
+    [a-z]+
+}
+"#,
+    )
+    .cst;
+    assert_lowered(
+        &cst,
+        &[concat!(
+            "and_then(|s|builtin_text(s,\"let\",false)).and_then(|s|builtin_ignore(s))",
+            ".and_then(|s|s.cut()).and_then(|s|builtin_ignore(s)).and_then(|s|parse_name(s)",
+        )],
+    );
+}
+
+#[test]
+fn export_tm_language() {
+    let mut info = optimized(
+        r#"
+grammar DemoLang {}
+
+entry class Program {
+    (Statement | Comment)*
+}
+class Statement {
+    'let' Name '=' Number ';' | KW_IF Name KW_ELSE Name
+}
+@style(keyword)
+token Keywords {
+    KW_IF: 'if'
+    KW_ELSE: 'else'
+}
+@style(comment)
+class Comment {
+    /#[^\n]*/
+}
+class Name {
+    /[a-z]+/
+}
+class Number {
+    /[0-9]+/
+}
+"#,
+    );
+    info.extensions = vec!["demo".to_string(), "*.dm".to_string()];
+    let out = match info.generate(VscodeCodegen::default()) {
+        Validation::Success { value, .. } => value,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    };
+    assert_lowered(
+        &out.grammar,
+        &[
+            "\"scopeName\": \"source.demo-lang\"",
+            // styled rules come first, then token sets, literals and regex rules
+            concat!(
+                "{ \"include\": \"#Comment\" },\n        { \"include\": \"#Keywords\" },\n",
+                "        { \"include\": \"#keywords\" },\n        { \"include\": \"#operators\" },",
+            ),
+            "\"name\": \"comment.demo-lang\",\n            \"match\": \"(?:#[^\\\\n]*)\"",
+            // the longest alternative is tried first
+            "\"name\": \"keyword.control.demo-lang\",\n            \"match\": \"(?:\\\\belse\\\\b|\\\\bif\\\\b)\"",
+            "\"name\": \"keyword.other.demo-lang\",\n            \"match\": \"\\\\blet\\\\b\"",
+            "\"name\": \"keyword.operator.demo-lang\",\n            \"match\": \"(?:;|=)\"",
+            "\"name\": \"constant.other.number.demo-lang\",\n            \"match\": \"(?:[0-9]+)\"",
+        ],
+    );
+    assert_lowered(
+        &out.package,
+        &[
+            "\"id\": \"demo-lang\"",
+            "\"extensions\": [\".demo\", \".dm\"]",
+            "\"path\": \"./syntaxes/demo-lang.tmLanguage.json\"",
+        ],
+    );
+}
+
+#[test]
+fn box_recursive_fields() {
+    let out = lower(
+        r#"
+grammar Demo {}
+
+entry class Program {
+    Block Path
+}
+class Path {
+    Name ('.' rest:Path)?
+}
+class Block {
+    '{' Statement* '}'
+}
+union Statement {
+    | Block
+    | IfStatement
+}
+class IfStatement {
+    'if' Name then:Statement ('else' otherwise:Statement)?
+}
+class Name {
+    /[a-z]+/
+}
+"#,
+    );
+    assert_lowered(
+        &out.main,
+        &[
+            "pub rest: Option<Box<PathNode>>,",
+            // `Vec` is already on the heap
+            "pub statement: Vec<StatementNode>,",
+            // the cycle is closed by the fields, not by the variant
+            "    Block(BlockNode),\n    IfStatement(IfStatementNode),",
+            "pub otherwise: Option<Box<StatementNode>>,\n    pub then: Box<StatementNode>,",
+        ],
+    );
+    assert_lowered(
+        &out.ast,
+        &[
+            "rest: pair.take_tagged_option::<PathNode>(Cow::Borrowed(\"rest\")).map(Box::new),",
+            "then: Box::new(pair.take_tagged_one::<StatementNode>(Cow::Borrowed(\"then\"))?),",
+        ],
+    );
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "dynamic")]
+
+use std::fmt::Debug;
+use yggdrasil_error::Validation;
+use yggdrasil_parser::{bootstrap::BootstrapRule, BootstrapParser};
+use yggdrasil_rt::{TokenTree, YggdrasilParser, YggdrasilRule};
+use yggdrasil_shared::{codegen::RustCodegen, interpreter::GrammarInterpreter, load_grammar, parse_grammar};
+
+/// The grammar of the generated bootstrap parser
+fn bootstrap() -> GrammarInterpreter {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../bootstrap/grammars/bootstrap.ygg");
+    match load_grammar(path) {
+        Validation::Success { value, .. } => GrammarInterpreter::new(&value).unwrap(),
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+/// One line for each node, `Rule tag start..end`
+fn flatten<R: YggdrasilRule + Debug>(tree: TokenTree<R>, depth: usize, out: &mut Vec<String>) {
+    for pair in tree {
+        let span = pair.get_span();
+        let tag = pair.get_tag().unwrap_or("_").to_string();
+        out.push(format!("{}{:?} {} {}..{}", "  ".repeat(depth), pair.get_rule(), tag, span.start(), span.end()));
+        flatten(pair.into_inner(), depth + 1, out);
+    }
+}
+
+fn assert_same_tree(input: &str) {
+    let (mut dynamic, mut generated) = (vec![], vec![]);
+    flatten(bootstrap().parse_cst(input, "Root").unwrap(), 0, &mut dynamic);
+    flatten(BootstrapParser::parse_cst(input, BootstrapRule::Root).unwrap(), 0, &mut generated);
+    assert_eq!(dynamic.join("\n"), generated.join("\n"));
+    assert!(dynamic[0].ends_with(&format!("0..{}", input.len())), "`{input}` is not parsed to the end");
+}
+
+#[test]
+fn same_as_generated() {
+    assert_same_tree("class A {\n    'a' B*\n}");
+    assert_same_tree("union Atom {\n    | Identifier #Name\n    | /[0-9]+/ #Number\n}");
+    assert_same_tree("grammar Demo {}\n// comment\nentry class Root { (A | !B) ~ C? }");
+}
+
+#[test]
+fn missing_rule() {
+    assert!(bootstrap().parse_cst("class A {}", "Missing").is_err());
+}
+
+#[test]
+fn literal_set() {
+    let info = match parse_grammar(
+        r#"
+grammar Demo {}
+
+entry class Expr {
+    Num Op Num (',' | ';')?
+}
+class Op {
+    '+' | '-'
+}
+class Num {
+    /[0-9]+/
+}
+"#,
+    ) {
+        Validation::Success { value, .. } => value,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    };
+    // a literal set in the root is matched by `s.match_trie`, the nested one by `builtin_trie`
+    let cst = match info.generate(RustCodegen::default()) {
+        Validation::Success { value, .. } => value.cst,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    };
+    assert!(cst.contains("s.match_trie({static TRIE"), "{cst}");
+    assert!(cst.contains("s.optional(|s|builtin_trie(s,{static TRIE"), "{cst}");
+    let mut tree = vec![];
+    flatten(GrammarInterpreter::new(&info).unwrap().parse_cst("1-2;", "Expr").unwrap(), 0, &mut tree);
+    assert_eq!(tree, ["Expr _ 0..4", "  Num num 0..1", "  Op op 1..2", "  Num num 2..3", "  IgnoreText _ 3..4"]);
+}
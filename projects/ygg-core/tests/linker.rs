@@ -0,0 +1,65 @@
+use std::path::Path;
+use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
+use yggdrasil_shared::{load_grammar, GrammarInfo};
+
+/// Link and optimize the grammar in `tests/linker`, returns the grammar and the diagnostics
+fn link(file: &str) -> (GrammarInfo, Vec<String>) {
+    match load_grammar(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/linker").join(file)) {
+        Validation::Success { value, diagnostics } => (value, diagnostics.iter().map(|e| e.to_string()).collect()),
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+fn fatal(file: &str) -> String {
+    match load_grammar(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/linker").join(file)) {
+        Validation::Success { .. } => panic!("expect `{file}` fails to link"),
+        Validation::Failure { fatal, .. } => fatal.to_string(),
+    }
+}
+
+fn rule_names(info: &GrammarInfo) -> Vec<&str> {
+    info.rules.keys().map(|s| s.as_str()).collect()
+}
+
+#[test]
+fn import_items() {
+    let (info, diagnostics) = link("import_items.ygg");
+    assert_eq!(rule_names(&info), ["Program", "Identifier", "Letter", "Digits"]);
+    assert!(diagnostics.is_empty(), "{diagnostics:#?}");
+}
+
+#[test]
+fn import_all() {
+    let (info, diagnostics) = link("import_all.ygg");
+    // the imported rules are not exported, so the unused ones are dropped
+    assert_eq!(rule_names(&info), ["Program", "Identifier", "Letter"]);
+    assert!(diagnostics.iter().any(|d| d.contains("rule `Number` is never used")), "{diagnostics:#?}");
+}
+
+#[test]
+fn inherit() {
+    let (info, diagnostics) = link("derived.ygg");
+    // overrides keep the position of the inherited rule, `Null` is removed
+    assert_eq!(rule_names(&info), ["Value", "Pair", "Atom", "Name", "Number", "Sign", "Text"]);
+    match &info.rules["Atom"].body {
+        GrammarBody::Union { branches } => assert_eq!(branches.len(), 3),
+        _ => panic!("`Atom` is not a union"),
+    }
+    // the overrides that change the ast are reported
+    let expected = [
+        "field `Null` of `Value` is removed by the override",
+        "field `value` of `Pair` changes from `Name` to `Number`",
+        "variant `Minus` of `Sign` is removed by the override",
+    ];
+    for message in expected {
+        assert!(diagnostics.iter().any(|d| d.contains(message)), "`{message}` is not reported in {diagnostics:#?}");
+    }
+    assert_eq!(diagnostics.len(), 3, "{diagnostics:#?}");
+}
+
+#[test]
+fn import_cycle() {
+    let error = fatal("cycle_a.ygg");
+    assert!(error.contains("import cycle: cycle_a.ygg -> cycle_b.ygg -> cycle_a.ygg"), "{error}");
+}
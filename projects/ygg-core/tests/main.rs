@@ -6,6 +6,12 @@ fn ready() {
     println!("it, works!")
 }
 
+mod codegen;
+mod interpreter;
+mod linker;
+mod optimize;
+mod tree_sitter;
+
 #[test]
 fn run_json5() {
     let builder = RustCodegen::default();
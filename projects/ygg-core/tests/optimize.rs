@@ -0,0 +1,346 @@
+use yggdrasil_error::Validation;
+use yggdrasil_ir::rule::GrammarBody;
+use yggdrasil_shared::{parse_grammar, ExpressionBody, GrammarInfo, YggdrasilExpression};
+
+/// Run all passes, returns the optimized grammar and the diagnostics
+fn optimize(grammar: &str) -> (GrammarInfo, Vec<String>) {
+    match parse_grammar(grammar) {
+        Validation::Success { value, diagnostics } => (value, diagnostics.iter().map(|e| e.to_string()).collect()),
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+/// Run all passes, returns the fatal error
+fn fatal(grammar: &str) -> String {
+    match parse_grammar(grammar) {
+        Validation::Success { value, .. } => panic!("expect failure, found rules {:?}", value.rules.keys()),
+        Validation::Failure { fatal, .. } => fatal.to_string(),
+    }
+}
+
+/// Run all passes, returns the fatal error and the other errors
+fn failure(grammar: &str) -> Vec<String> {
+    match parse_grammar(grammar) {
+        Validation::Success { value, .. } => panic!("expect failure, found rules {:?}", value.rules.keys()),
+        Validation::Failure { fatal, diagnostics } => {
+            [fatal].iter().chain(diagnostics.iter()).map(|e| e.to_string()).collect()
+        }
+    }
+}
+
+/// The items of a class rule, `'text'` for literals and `/regex/` for regex, ignored rules are skipped
+fn sequence(info: &GrammarInfo, rule: &str) -> Vec<String> {
+    let term = match &info.rules[rule].body {
+        GrammarBody::Class { term } => term,
+        _ => panic!("`{rule}` is not a class"),
+    };
+    let items = match &term.body {
+        ExpressionBody::Concat(node) => node.sequence.iter().collect(),
+        _ => vec![term],
+    };
+    items.into_iter().filter(|e| !matches!(e.body, ExpressionBody::Ignored)).map(item).collect()
+}
+
+fn item(expr: &YggdrasilExpression) -> String {
+    match &expr.body {
+        ExpressionBody::Text(v) => format!("'{}'", v.text),
+        ExpressionBody::Regex(v) => format!("/{}/", v.raw),
+        ExpressionBody::Rule(v) => v.name.text.clone(),
+        ExpressionBody::Choice(v) => v.branches.iter().map(item).collect::<Vec<_>>().join(" | "),
+        other => format!("{other:?}"),
+    }
+}
+
+fn assert_reported(diagnostics: &[String], expected: &str) {
+    assert!(diagnostics.iter().any(|d| d.contains(expected)), "`{expected}` is not reported in {diagnostics:#?}")
+}
+
+#[test]
+fn dead_code() {
+    let (info, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+entry class Program {
+    Statement*
+}
+class Statement {
+    [a-z] ';'
+}
+class Unused {
+    Unused? Statement
+}
+ignore class Space {
+    ' '
+}
+"#,
+    );
+    let rules: Vec<_> = info.rules.keys().map(|s| s.as_str()).collect();
+    assert_eq!(rules, ["Program", "Statement", "Space"]);
+    assert_reported(&diagnostics, "rule `Unused` is never used");
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:#?}");
+}
+
+#[test]
+fn dead_code_without_entry() {
+    let (info, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+class Statement {
+    [a-z] ';'
+}
+class Unused {
+    'u'
+}
+"#,
+    );
+    assert_eq!(info.rules.len(), 2);
+    assert_reported(&diagnostics, "grammar `Demo` has no `entry` rule, unused rules are kept");
+}
+
+#[test]
+fn left_recursion_seed() {
+    let (info, _) = optimize(
+        r#"
+grammar Demo {}
+
+entry class A {
+    | B 'x'
+    | C
+}
+class B {
+    | A 'y'
+    | 'b'
+}
+class C {
+    'c' C?
+}
+"#,
+    );
+    assert!(info.rules["A"].left_recursive);
+    assert!(info.rules["B"].left_recursive);
+    // right recursion consumes input first
+    assert!(!info.rules["C"].left_recursive);
+}
+
+#[test]
+fn left_recursion_without_base_case() {
+    let error = fatal(
+        r#"
+grammar Demo {}
+
+entry class A {
+    B 'x'
+}
+class B {
+    A 'y'
+}
+"#,
+    );
+    assert!(error.contains("left recursion without a base case: A -> B -> A"), "{error}");
+}
+
+#[test]
+fn left_recursion_through_lookahead() {
+    let error = fatal(
+        r#"
+grammar Demo {}
+
+entry class A {
+    | &A 'x'
+    | 'a'
+}
+"#,
+    );
+    assert!(error.contains("left recursion through lookahead is not supported: A -> A"), "{error}");
+}
+
+#[test]
+fn inline_rules() {
+    let (info, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+entry class A {
+    B _C D C
+}
+inline class B {
+    'b'
+}
+class C {
+    'c'
+}
+inline class D {
+    'd' D?
+}
+"#,
+    );
+    assert_eq!(sequence(&info, "A"), ["'b'", "'c'", "D", "C"]);
+    // `B` is inlined everywhere, the recursive `D` is kept
+    assert!(!info.rules.contains_key("B"));
+    assert_reported(&diagnostics, "recursive rule `D` can't be inlined: D -> D");
+}
+
+#[test]
+fn fuse_rules() {
+    let (info, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+entry class A {
+    B C D
+}
+class B {
+    ASCII_DIGIT | '&' | '~' | '-' | '_'
+}
+class C {
+    | 'for' | 'foreach'
+}
+class D {
+    | 'a' B
+    | 'a' C
+}
+"#,
+    );
+    // the characters with special meaning in a class are escaped
+    assert_eq!(sequence(&info, "B"), [r"/[0-9\&\~\-_]/"]);
+    assert_eq!(sequence(&info, "C"), ["'for'"]);
+    assert_reported(&diagnostics, "branch 2 of `C` is never matched, \"for\" comes first");
+    // `'a' (B | C)`
+    let d = sequence(&info, "D");
+    assert_eq!((d.len(), d[0].as_str()), (2, "'a'"));
+}
+
+#[test]
+fn first_set_lints() {
+    let (_, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+entry class A {
+    B C D E
+}
+class B {
+    | 'x'? | 'y'
+}
+class C {
+    | ASCII_DIGIT | '7' 'z'
+}
+class D {
+    ('d'?)*
+}
+class E {
+    &'e'
+}
+"#,
+    );
+    assert_reported(&diagnostics, "branch 2 of `B` is never matched, the previous branch never fails");
+    assert_reported(&diagnostics, "branch 2 of `C` is never matched, the first character always matches the previous branch");
+    assert_reported(&diagnostics, "the body of the repetition may match empty input, it loops forever");
+    assert_reported(&diagnostics, "rule `E` never consumes any input");
+    assert_eq!(diagnostics.len(), 4, "{diagnostics:#?}");
+}
+
+#[test]
+fn expand_macros() {
+    let (info, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+macro pair(key, value) {
+    key '=' value
+}
+entry class A {
+    P D
+}
+class P {
+    @pair('k', E)
+}
+class D {
+    @keyword('if', 'else')
+}
+class E {
+    'e'
+}
+"#,
+    );
+    assert_eq!(sequence(&info, "P"), ["'k'", "'='", "E"]);
+    assert_eq!(sequence(&info, "D"), [r"/if(?-u:\b)/ | /else(?-u:\b)/"]);
+    assert!(diagnostics.is_empty(), "{diagnostics:#?}");
+}
+
+#[test]
+fn unexpanded_macros() {
+    let errors = failure(
+        r#"
+grammar Demo {}
+
+macro pair(key, value) {
+    key '=' value
+}
+macro nested(x) {
+    x @nested(x)
+}
+entry class A {
+    B C
+}
+class B {
+    @pair('x')
+}
+class C {
+    @nested('y') @nothing()
+}
+"#,
+    );
+    // every call that can't be expanded is reported, the first one is fatal
+    assert!(errors[0].contains("macro `@pair` expects 2 arguments, found 1"), "{errors:#?}");
+    assert_reported(&errors, "recursive macro `@nested` can't be expanded: nested -> nested");
+    assert_reported(&errors, "unknown macro `@nothing`");
+    assert_eq!(errors.len(), 3, "{errors:#?}");
+}
+
+#[test]
+fn resolve_names() {
+    let (_, diagnostics) = optimize(
+        r#"
+grammar Demo {}
+
+entry class A {
+    B
+}
+class B {
+    'b'
+}
+class B {
+    'x'
+}
+"#,
+    );
+    // the first definition is labeled
+    assert_reported(&diagnostics, "rule `B` is defined twice at 68..69\n    first defined here at 48..49");
+    let error = fatal(
+        r#"
+grammar Demo {}
+
+entry class A {
+    Statment
+}
+class Statement {
+    'b'
+}
+"#,
+    );
+    assert!(error.contains("rule `Statment` is not defined, did you mean `Statement`?"), "{error}");
+    let error = fatal(
+        r#"
+grammar Demo {}
+
+entry class A {
+    first:'b' FIRST:'c'
+}
+"#,
+    );
+    assert!(error.contains("field `FIRST` of `A` collides with `first`"), "{error}");
+    assert!(error.contains("`first` is defined here"), "{error}");
+}
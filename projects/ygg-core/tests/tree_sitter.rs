@@ -0,0 +1,100 @@
+#![cfg(feature = "tree-sitter")]
+
+use yggdrasil_error::Validation;
+use yggdrasil_shared::{codegen::TreeSitterCodegen, parse_grammar};
+
+/// The `grammar.js` and the diagnostics, one line each
+fn export(grammar: &str) -> (String, Vec<String>) {
+    let info = match parse_grammar(grammar) {
+        Validation::Success { value, .. } => value,
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    };
+    match info.generate(TreeSitterCodegen::default()) {
+        Validation::Success { value, diagnostics } => (value, diagnostics.iter().map(|e| e.to_string()).collect()),
+        Validation::Failure { fatal, .. } => panic!("{fatal}"),
+    }
+}
+
+#[test]
+fn export_grammar_js() {
+    let (js, diagnostics) = export(
+        r#"
+grammar DemoLang {}
+
+entry class Program {
+    Statement*
+}
+class Statement {
+    'let' key:Name '=' Value ';' | !'let' Name ';'
+}
+union Value {
+    | Name
+    | Never
+    | Maybe
+    | Stop
+}
+class Name {
+    /[a-z]+/
+}
+class Never {
+    false
+}
+class Maybe {
+    true 'm' (!'m')*
+}
+class Stop {
+    's' false
+}
+ignore class WhiteSpace {
+    /\s+/
+}
+"#,
+    );
+    let expected = [
+        "name: 'demo_lang',",
+        "extras: $ => [\n        $.white_space,\n    ],",
+        // the entry rule comes first
+        "rules: {\n        program: $ => repeat(field('statement', $.statement)),",
+        // the lookahead is dropped, the ignored rules are in `extras`
+        concat!(
+            "statement: $ => choice(seq('let', field('key', $.name), '=', field('value', $.value), ';'), ",
+            "seq(field('name', $.name), ';')),",
+        ),
+        concat!(
+            "value: $ => choice(field('name', $.name), field('never', $.never), ",
+            "field('maybe', $.maybe), field('stop', $.stop)),",
+        ),
+        "name: $ => /[a-z]+/,",
+        "never: $ => blank(),",
+        // `true` always matches, the repeated lookahead is not wrapped in `repeat`
+        "maybe: $ => 'm',",
+        "stop: $ => 's',",
+    ];
+    for part in expected {
+        assert!(js.contains(part), "`{part}` is not found in:\n{js}")
+    }
+    assert_eq!(
+        diagnostics,
+        [
+            // at the operand of `!`
+            concat!(
+                "SyntaxError: lookahead in rule `Statement` can't be expressed in tree-sitter, ",
+                "it's replaced by `blank()` at 116..119",
+            ),
+            // constants have no span, the whole rule is reported
+            concat!(
+                "SyntaxError: constant in rule `Never` can't be expressed in tree-sitter, ",
+                "it's replaced by `blank()` at 222..247",
+            ),
+            concat!(
+                "SyntaxError: lookahead in rule `Maybe` can't be expressed in tree-sitter, ",
+                "it's replaced by `blank()` at 278..279",
+            ),
+            // or the enclosing sequence
+            concat!(
+                "SyntaxError: constant in rule `Stop` can't be expressed in tree-sitter, ",
+                "it's replaced by `blank()` at 303..304",
+            ),
+        ]
+    );
+}
@@ -15,7 +15,8 @@ pub enum YggdrasilErrorKind {
     Io { error: String, file: Option<PathBuf> },
     Runtime { message: String },
     Config { message: String },
-    Syntax { message: String, range: Range<usize> },
+    /// The labels point to other places related to the error
+    Syntax { message: String, range: Range<usize>, labels: Vec<(String, Range<usize>)> },
 }
 
 impl Error for YggdrasilError {}
@@ -51,8 +52,12 @@ impl Display for YggdrasilErrorKind {
             YggdrasilErrorKind::Config { message } => {
                 write!(f, "ConfigError: {}", message)
             }
-            YggdrasilErrorKind::Syntax { message, range } => {
-                write!(f, "SyntaxError: {} at {:?}", message, range)
+            YggdrasilErrorKind::Syntax { message, range, labels } => {
+                write!(f, "SyntaxError: {} at {:?}", message, range)?;
+                for (label, range) in labels {
+                    write!(f, "\n    {} at {:?}", label, range)?
+                }
+                Ok(())
             }
         }
     }
@@ -66,6 +71,13 @@ impl YggdrasilError {
         Self { kind: Box::new(YggdrasilErrorKind::Runtime { message: message.to_string() }) }
     }
     pub fn syntax_error<S: Display>(message: S, range: Range<usize>) -> Self {
-        Self { kind: Box::new(YggdrasilErrorKind::Syntax { message: message.to_string(), range }) }
+        Self { kind: Box::new(YggdrasilErrorKind::Syntax { message: message.to_string(), range, labels: vec![] }) }
+    }
+    /// Add a label to the syntax error, e.g. the first definition of a duplicated rule
+    pub fn with_label<S: Display>(mut self, message: S, range: Range<usize>) -> Self {
+        if let YggdrasilErrorKind::Syntax { labels, .. } = self.kind.as_mut() {
+            labels.push((message.to_string(), range))
+        }
+        self
     }
 }
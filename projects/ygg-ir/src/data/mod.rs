@@ -5,7 +5,7 @@ mod symbol;
 mod text;
 
 pub use self::{
-    regex_category::YggdrasilRegex,
+    regex_category::{YggdrasilRegex, YggdrasilRegexCompiled},
     rule_ref::RuleReference,
     symbol::{Symbol, SymbolAlias},
     text::YggdrasilText,
@@ -1,5 +1,5 @@
 use super::*;
-use regex_automata::dfa::dense::BuildError;
+use regex_automata::dfa::{dense::BuildError, regex::Regex};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -34,43 +34,67 @@ impl YggdrasilRegex {
     {
         Self { raw: text.to_string(), span }
     }
-    pub fn build(&mut self) -> Result<(), BuildError> {
-        // let regex = Regex::new(&self.to_string())?;
-        // let (fwd_bytes, fwd_pad) = regex.forward().to_bytes_little_endian();
-        // let (rev_bytes, rev_pad) = regex.reverse().to_bytes_little_endian();
-        // self.forward_le = fwd_bytes[fwd_pad..].to_vec();
-        // self.reverse_le = rev_bytes[rev_pad..].to_vec();
-        // let (fwd_bytes, fwd_pad) = regex.forward().to_bytes_big_endian();
-        // let (rev_bytes, rev_pad) = regex.reverse().to_bytes_big_endian();
-        // self.forward_be = fwd_bytes[fwd_pad..].to_vec();
-        // self.reverse_be = rev_bytes[rev_pad..].to_vec();
-        Ok(())
+    /// The pattern matched at the start of the input
+    pub fn pattern(&self) -> String {
+        format!("^({})", self.raw)
     }
-    pub fn built(&self) -> Result<Self, BuildError> {
-        let mut out = self.clone();
-        out.build()?;
-        Ok(out)
+    /// Serialize the forward and reverse dense DFAs in both endiannesses
+    pub fn build(&self) -> Result<YggdrasilRegexCompiled, BuildError> {
+        let regex = Regex::new(&self.pattern())?;
+        let strip = |(bytes, pad): (Vec<u8>, usize)| bytes[pad..].to_vec();
+        Ok(YggdrasilRegexCompiled {
+            name: self.constant_name(),
+            raw: self.raw.clone(),
+            forward_le: strip(regex.forward().to_bytes_little_endian()),
+            reverse_le: strip(regex.reverse().to_bytes_little_endian()),
+            forward_be: strip(regex.forward().to_bytes_big_endian()),
+            reverse_be: strip(regex.reverse().to_bytes_big_endian()),
+        })
     }
 }
 
-// impl Display for YggdrasilRegex {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-//         writeln!(f, "/// `{}`", self.raw)?;
-//         writeln!(f, "#[rustfmt::skip]")?;
-//         writeln!(f, "const {}: RegexCompiled = RegexCompiled {{", self.constant_name())?;
-//         writeln!(f, "    forward_le: &{:?},", self.forward_le)?;
-//         writeln!(f, "    reverse_le: &{:?},", self.reverse_le)?;
-//         writeln!(f, "    forward_be: &{:?},", self.forward_be)?;
-//         writeln!(f, "    reverse_be: &{:?},", self.reverse_be)?;
-//         f.write_str("};")?;
-//         Ok(())
-//     }
-// }
+/// The DFAs of a [`YggdrasilRegex`], written as a `RegexCompiled` constant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YggdrasilRegexCompiled {
+    pub name: String,
+    pub raw: String,
+    pub forward_le: Vec<u8>,
+    pub reverse_le: Vec<u8>,
+    pub forward_be: Vec<u8>,
+    pub reverse_be: Vec<u8>,
+}
+
+impl Display for YggdrasilRegexCompiled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "/// {:?}", self.raw)?;
+        writeln!(f, "#[rustfmt::skip]")?;
+        writeln!(f, "const {}: RegexCompiled = RegexCompiled {{", self.name)?;
+        writeln!(f, "    raw: {:?},", self.raw)?;
+        for (name, bytes) in [
+            ("forward_le", &self.forward_le),
+            ("reverse_le", &self.reverse_le),
+            ("forward_be", &self.forward_be),
+            ("reverse_be", &self.reverse_be),
+        ] {
+            write!(f, "    {name}: &RegexAligned {{ align: [], bytes: *b\"")?;
+            for byte in bytes {
+                match byte {
+                    b'"' | b'\\' => write!(f, "\\{}", *byte as char)?,
+                    0x20..=0x7E => f.write_char(*byte as char)?,
+                    _ => write!(f, "\\x{:02x}", byte)?,
+                }
+            }
+            writeln!(f, "\" }}.bytes,")?;
+        }
+        f.write_str("};")
+    }
+}
 
 impl YggdrasilRegex {
+    /// The same pattern gets the same name, wherever it is
     pub fn constant_name(&self) -> String {
         let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
+        self.raw.hash(&mut hasher);
         let id = hasher.finish();
         format!("REGEX_{:X}", id)
     }
@@ -32,6 +32,7 @@ impl From<YggdrasilIdentifier> for YggdrasilExpression {
         let out: ExpressionBody = match name {
             p if properties.contains(&p) => ExpressionBody::Regex(YggdrasilRegex::new(&format!("[\\p{{{name}}}]"), 0..p.len())),
             "ANY" => ExpressionBody::CharacterAny.into(),
+            "ROL" => ExpressionBody::CharacterRestOfLine,
             "IGNORE" | "IGNORED" => ExpressionBody::Ignored.into(),
             "ASCII_DIGIT" => ExpressionBody::CharacterRange(RangeInclusive::new('0', '9')),
             _ => ExpressionBody::Rule(RuleReference::new(value)),
@@ -15,8 +15,22 @@ pub struct GrammarInfo {
     /// File path of the grammar
     pub url: Option<Url>,
     pub name: YggdrasilIdentifier,
+    /// The inherited grammar, `grammar Json5 : Json` inherits the rules in `json.ygg` next to this file
+    pub base: Option<YggdrasilIdentifier>,
+    /// Rules of the base grammar that are not inherited
+    ///
+    /// ```ygg
+    /// grammar Json5 : Json { -Null }
+    /// ```
+    pub removed: Vec<YggdrasilIdentifier>,
     pub extensions: Vec<String>,
+    /// Rules imported by `using`, an empty list imports all rules defined in the file
+    ///
+    /// ```ygg
+    /// using "common.ygg" { Identifier, String as Str }
+    /// ```
     pub imports: BTreeMap<Url, Vec<SymbolAlias>>,
+    /// Rules kept by the dead code elimination besides the `entry` rules
     pub exports: Vec<String>,
     pub rules: IndexMap<String, GrammarRule>,
     /// Named set of rules
@@ -38,6 +52,8 @@ impl Default for GrammarInfo {
         Self {
             url: None,
             name: Default::default(),
+            base: None,
+            removed: vec![],
             extensions: vec![],
             imports: Default::default(),
             exports: vec![],
@@ -56,7 +72,7 @@ impl GrammarInfo {
         self.rules.values().cloned().collect()
     }
     pub fn ignored_rules(&self) -> Vec<GrammarRule> {
-        todo!()
+        self.rules.values().filter(|r| r.ignored).cloned().collect()
     }
     pub fn insert(&mut self, rule: GrammarRule) -> Option<GrammarRule> {
         let key = rule.name.text.clone();
@@ -14,3 +14,4 @@ mod utils;
 mod parser;
 
 pub use indexmap::{IndexMap, IndexSet};
+pub use url::Url;
@@ -58,10 +58,10 @@ impl AddAssign for YggdrasilExpression {
     /// `a ~ b ~ c`
     fn add_assign(&mut self, rhs: Self) {
         match &mut self.body {
-            ExpressionBody::Concat(this) if self.tag.is_none() && rhs.tag.is_none() => {
+            ExpressionBody::Concat(this) if self.tag.is_none() => {
                 this.sequence.push(YggdrasilExpression::ignored());
                 match rhs.body {
-                    ExpressionBody::Concat(that) => this.sequence.extend(that.sequence),
+                    ExpressionBody::Concat(that) if rhs.tag.is_none() => this.sequence.extend(that.sequence),
                     _ => this.sequence.push(rhs),
                 }
                 return;
@@ -85,9 +85,9 @@ impl BitAndAssign for YggdrasilExpression {
     /// `p:a q:(b c) | d e`
     fn bitand_assign(&mut self, rhs: Self) {
         match &mut self.body {
-            ExpressionBody::Concat(this) if self.tag.is_none() && rhs.tag.is_none() => {
+            ExpressionBody::Concat(this) if self.tag.is_none() => {
                 match rhs.body {
-                    ExpressionBody::Concat(that) => this.sequence.extend(that.sequence),
+                    ExpressionBody::Concat(that) if rhs.tag.is_none() => this.sequence.extend(that.sequence),
                     _ => this.sequence.push(rhs),
                 }
                 return;
@@ -37,6 +37,23 @@ impl YggdrasilExpression {
     pub fn with_remark(self) -> Self {
         Self { remark: true, ..self }
     }
+    /// The span of the atoms in the source, constants and builtins have no span
+    pub fn get_range(&self) -> Option<Range<usize>> {
+        let children = match &self.body {
+            ExpressionBody::Choice(v) => &v.branches,
+            ExpressionBody::Concat(v) => &v.sequence,
+            ExpressionBody::Unary(v) => return v.base.get_range(),
+            ExpressionBody::Rule(v) => return Some(v.name.range.clone()),
+            ExpressionBody::Text(v) => return Some(v.range.clone()),
+            ExpressionBody::Regex(v) => return Some(v.span.clone()),
+            ExpressionBody::Call(v) => return Some(v.range.clone()),
+            _ => return None,
+        };
+        let ranges: Vec<_> = children.iter().filter_map(|e| e.get_range()).collect();
+        let start = ranges.iter().map(|r| r.start).min()?;
+        let end = ranges.iter().map(|r| r.end).max()?;
+        Some(start..end)
+    }
 }
 
 impl ExpressionBody {
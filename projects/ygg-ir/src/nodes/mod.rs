@@ -1,7 +1,7 @@
 use std::{
     fmt::{Debug, Formatter},
     hash::{Hash, Hasher},
-    ops::{Add, BitAnd, BitOr, RangeInclusive},
+    ops::{Add, BitAnd, BitOr, Range, RangeInclusive},
 };
 
 use convert_case::{Case, Casing};
@@ -48,6 +48,8 @@ pub enum ExpressionBody {
     Text(YggdrasilText),
     /// Any ignored rule
     Ignored,
+    /// `@cut()`, the failures after it in the sequence are final
+    Cut,
     /// Any character
     CharacterAny,
     CharacterRestOfLine,
@@ -74,6 +76,7 @@ impl Debug for ExpressionBody {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ExpressionBody::Ignored => f.write_str("IGNORED"),
+            ExpressionBody::Cut => f.write_str("CUT"),
             ExpressionBody::Choice(v) => Debug::fmt(v, f),
             ExpressionBody::Concat(v) => Debug::fmt(v, f),
             ExpressionBody::Call(v) => Debug::fmt(v, f),
@@ -106,6 +109,9 @@ impl YggdrasilExpression {
     pub fn ignored() -> Self {
         ExpressionBody::Ignored.into()
     }
+    pub fn cut() -> Self {
+        ExpressionBody::Cut.into()
+    }
     pub fn any() -> Self {
         ExpressionBody::CharacterAny.into()
     }
@@ -17,6 +17,14 @@ pub enum YggdrasilOperator {
     RepeatsBetween { min: u32, max: u32 },
     /// *e
     Recursive,
+    /// ```ygg
+    /// @label("expression", e)
+    /// ```
+    Label(String),
+    /// ```ygg
+    /// @error("expected a closing brace", e)
+    /// ```
+    Error(String),
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
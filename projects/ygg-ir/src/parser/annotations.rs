@@ -1,7 +1,7 @@
 use super::*;
 
 impl GrammarRule {
-    pub(super) fn with_annotation(mut self, extra: TakeAnnotations) -> Self {
+    pub(super) fn with_annotation(mut self, extra: TakeAnnotations, errors: &mut Vec<YggdrasilError>) -> Self {
         match extra.get_atomic() {
             Some(true) => self.atomic = GrammarAtomic::Atomic,
             Some(false) => self.atomic = GrammarAtomic::Combined,
@@ -13,15 +13,53 @@ impl GrammarRule {
         if let Some(s) = extra.get_keep() {
             self.hide = s
         };
+        if let Some(s) = extra.get_memo() {
+            self.memo = s
+        };
+        if let Some(s) = extra.get_inline() {
+            self.auto_inline = s
+        };
         if let Some(s) = extra.get_entry() {
             self.entry = s
         };
+        if let Some(s) = extra.get_extend() {
+            self.extend = s
+        };
         if let Some(s) = extra.get_auto_capture() {
             self.captures.auto = s
         };
         if let Some(s) = extra.get_text_capture() {
             self.captures.text = s
         }
+        for sync in extra.get_recover() {
+            match YggdrasilExpression::build_or(sync) {
+                Ok(o) => self.recover.push(o),
+                Err(e) => errors.push(e),
+            }
+        }
+        if let Some(s) = extra.get_label() {
+            self.label = build_message(s, "label", errors)
+        }
+        if let Some(s) = extra.get_error() {
+            self.error = build_message(s, "error", errors)
+        }
+        self.styles.extend(extra.get_styles());
         self
     }
 }
+
+/// The string argument of `@label` and `@error`
+fn build_message(node: &ExpressionNode, name: &str, errors: &mut Vec<YggdrasilError>) -> Option<String> {
+    match YggdrasilExpression::build_or(node) {
+        Ok(YggdrasilExpression { body: ExpressionBody::Text(v), .. }) => Some(v.text),
+        Ok(_) => {
+            let message = format!("decorator `@{name}` expects a string");
+            errors.push(YggdrasilError::syntax_error(message, node.get_range().unwrap_or_default()));
+            None
+        }
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    }
+}
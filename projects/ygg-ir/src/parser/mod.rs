@@ -1,20 +1,25 @@
-use std::str::FromStr;
+use std::{env::current_dir, str::FromStr};
 
-use yggdrasil_error::YggdrasilError;
+use yggdrasil_error::{Failure, Success, Validation, YggdrasilError};
 use yggdrasil_parser::{
     bootstrap::{
-        AtomicNode, BooleanNode, ClassStatementNode, ExpressionHardNode, ExpressionNode, ExpressionSoftNode, ExpressionTagNode,
-        GrammarStatementNode, GroupPairNode, GroupStatementNode, IdentifierNode, PrefixNode, RootNode, StatementNode, StringItemNode,
-        StringNode, SuffixNode, TermNode, UnionBranchNode, UnionStatementNode,
+        AtomicNode, BooleanNode, ClassStatementNode, ClimbStatementNode, ExpressionHardNode, ExpressionNode, ExpressionSoftNode,
+        ExpressionTagNode, FunctionCallNode, GrammarStatementNode, GroupPairNode, GroupStatementNode, IdentifierNode, ImportStatementNode,
+        MacroStatementNode, PrefixNode, RootNode, StatementNode, StringItemNode, StringNode, SuffixNode, TermNode, UnionBranchNode,
+        UnionStatementNode,
     },
     TakeAnnotations, YggdrasilNode,
 };
+use url::Url;
 
 use crate::{
-    data::{YggdrasilRegex, YggdrasilText},
+    data::{SymbolAlias, YggdrasilRegex, YggdrasilText},
     grammar::GrammarInfo,
-    nodes::{UnaryExpression, YggdrasilExpression, YggdrasilOperator},
-    rule::{GrammarAtomic, GrammarBody, GrammarRule, YggdrasilIdentifier, YggdrasilVariant},
+    nodes::{ExpressionBody, UnaryExpression, YggdrasilExpression, YggdrasilOperator},
+    rule::{
+        FunctionRule, GrammarAtomic, GrammarBody, GrammarRule, YggdrasilIdentifier, YggdrasilMacroArgument, YggdrasilMacroCall,
+        YggdrasilNamepath, YggdrasilVariant,
+    },
 };
 
 mod annotations;
@@ -22,70 +27,112 @@ mod annotations;
 impl FromStr for GrammarInfo {
     type Err = YggdrasilError;
 
+    /// Fails with the first error, use [`GrammarInfo::from_source`] to get all errors
     fn from_str(s: &str) -> Result<Self, YggdrasilError> {
-        GrammarInfo::try_from(RootNode::from_str(s)?)
+        match GrammarInfo::from_source(s, None) {
+            Success { value, diagnostics } => match diagnostics.into_iter().next() {
+                Some(e) => Err(e),
+                None => Ok(value),
+            },
+            Failure { fatal, .. } => Err(fatal),
+        }
     }
 }
 
-impl TryFrom<RootNode> for GrammarInfo {
-    type Error = YggdrasilError;
-
-    fn try_from(value: RootNode) -> Result<Self, Self::Error> {
-        let mut out = GrammarInfo::default();
-        for s in &value.statement {
-            match s {
-                StatementNode::GrammarStatement(v) => out.visit_grammar(v)?,
-                StatementNode::ClassStatement(v) => match GrammarRule::build_class(v) {
-                    Ok(o) => {
-                        out.rules.insert(o.name.text.clone(), o);
-                    }
-                    Err(e) => {
-                        println!("{e:?}");
-                    }
-                },
-                StatementNode::UnionStatement(v) => match GrammarRule::build_union(v) {
-                    Ok(o) => {
-                        out.rules.insert(o.name.text.clone(), o);
-                    }
-                    Err(e) => {
-                        println!("{e:?}");
-                    }
-                },
-                StatementNode::GroupStatement(v) => match GrammarRule::build_group(v) {
-                    Ok((id, terms)) => match id {
-                        Some(id) => {
-                            let mut name = vec![];
-                            for o in terms {
-                                name.push(o.name.clone());
-                                out.rules.insert(o.name.text.clone(), o);
-                            }
-                            out.token_sets.insert(id.text.clone(), name);
-                        }
-                        None => {
-                            for o in terms {
-                                out.rules.insert(o.name.text.clone(), o);
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        println!("{e:?}");
-                    }
-                },
-            }
+impl GrammarInfo {
+    /// Parse the grammar file at `url`, the paths in `using` are relative to it, or to the working directory if `url` is `None`
+    pub fn from_source(input: &str, url: Option<Url>) -> Validation<Self> {
+        match RootNode::from_str(input) {
+            Ok(o) => GrammarInfo::from_root(&o, url),
+            Err(e) => Failure { fatal: e.into(), diagnostics: vec![] },
         }
-        Ok(out)
+    }
+    /// Build the grammar from the ast, rules with errors are skipped so that all errors are reported at once
+    pub fn from_root(root: &RootNode, url: Option<Url>) -> Validation<Self> {
+        let mut out = GrammarInfo { url, ..Default::default() };
+        let mut errors = vec![];
+        out.visit_root(root, &mut errors);
+        Success { value: out, diagnostics: errors }
     }
 }
 
 impl GrammarInfo {
+    fn visit_root(&mut self, value: &RootNode, errors: &mut Vec<YggdrasilError>) {
+        for s in &value.statement {
+            let result = match s {
+                StatementNode::GrammarStatement(v) => self.visit_grammar(v),
+                StatementNode::ImportStatement(v) => self.visit_import(v),
+                StatementNode::ClassStatement(v) => GrammarRule::build_class(v, errors).map(|o| self.define(o, errors)),
+                StatementNode::UnionStatement(v) => GrammarRule::build_union(v, errors).map(|o| self.define(o, errors)),
+                StatementNode::ClimbStatement(v) => GrammarRule::build_climb(v, errors).map(|o| self.define(o, errors)),
+                StatementNode::GroupStatement(v) => {
+                    self.visit_group(v, errors);
+                    Ok(())
+                }
+                StatementNode::MacroStatement(v) => FunctionRule::build(v).map(|o| {
+                    self.functions.insert(o.name.text.clone(), o);
+                }),
+            };
+            if let Err(e) = result {
+                errors.push(e)
+            }
+        }
+    }
+    /// The first definition is kept if a rule is defined twice
+    fn define(&mut self, rule: GrammarRule, errors: &mut Vec<YggdrasilError>) {
+        match self.rules.get(&rule.name.text) {
+            Some(old) => {
+                let message = format!("rule `{}` is defined twice", rule.name.text);
+                let error = YggdrasilError::syntax_error(message, rule.name.range);
+                errors.push(error.with_label("first defined here", old.name.range.clone()))
+            }
+            None => {
+                self.insert(rule);
+            }
+        }
+    }
+    fn visit_group(&mut self, node: &GroupStatementNode, errors: &mut Vec<YggdrasilError>) {
+        let (id, terms) = GrammarRule::build_group(node, errors);
+        let mut names = vec![];
+        for o in terms {
+            names.push(o.name.clone());
+            self.define(o, errors);
+        }
+        if let Some(id) = id {
+            self.token_sets.insert(id.text.clone(), names);
+        }
+    }
     fn visit_grammar(&mut self, node: &GrammarStatementNode) -> Result<(), YggdrasilError> {
         self.name = YggdrasilIdentifier::build(&node.identifier);
+        self.base = node.base.as_ref().map(YggdrasilIdentifier::build);
+        self.removed = node.grammar_block.identifier.iter().map(YggdrasilIdentifier::build).collect();
+        Ok(())
+    }
+    fn visit_import(&mut self, node: &ImportStatementNode) -> Result<(), YggdrasilError> {
+        let path = build_string(&node.path);
+        let url = match &self.url {
+            Some(base) => base.join(&path).ok(),
+            None => Url::from_file_path(current_dir()?.join(&path)).ok(),
+        };
+        let url = match url {
+            Some(s) => s,
+            None => {
+                let message = format!("invalid import path `{path}`");
+                return Err(YggdrasilError::syntax_error(message, node.path.get_range().unwrap_or_default()));
+            }
+        };
+        let symbols = self.imports.entry(url).or_default();
+        for item in node.import_block.iter().flat_map(|v| &v.import_item) {
+            let name = item.name.text.clone();
+            let alias = item.alias.as_ref().map(|v| v.text.clone()).unwrap_or_else(|| name.clone());
+            symbols.push(SymbolAlias { name, alias, range: item.get_range().unwrap_or_default() })
+        }
         Ok(())
     }
 }
 
 impl GrammarRule {
-    fn build_class(node: &ClassStatementNode) -> Result<Self, YggdrasilError> {
+    fn build_class(node: &ClassStatementNode, errors: &mut Vec<YggdrasilError>) -> Result<Self, YggdrasilError> {
         let name = YggdrasilIdentifier::build(&node.name);
         let rule = Self {
             name,
@@ -93,7 +140,7 @@ impl GrammarRule {
             range: node.get_range().unwrap_or_default(),
             ..Default::default()
         }
-        .with_annotation(node.annotations());
+        .with_annotation(node.annotations(), errors);
         Ok(rule)
     }
     fn build_class_in_group(node: &GroupPairNode) -> Result<Self, YggdrasilError> {
@@ -106,29 +153,75 @@ impl GrammarRule {
         };
         Ok(rule)
     }
-    fn build_union(node: &UnionStatementNode) -> Result<Self, YggdrasilError> {
+    /// Branches with errors are skipped
+    fn build_union(node: &UnionStatementNode, errors: &mut Vec<YggdrasilError>) -> Result<Self, YggdrasilError> {
         let name = YggdrasilIdentifier::build(&node.name);
         let mut branches = vec![];
         for branch in &node.union_block.union_branch {
             match YggdrasilExpression::build_tag_branch(branch) {
                 Ok(o) => branches.push(o),
-                Err(_) => {}
+                Err(e) => errors.push(e),
             }
         }
         let rule = Self { name, body: GrammarBody::Union { branches }, range: node.get_range().unwrap_or_default(), ..Default::default() }
-            .with_annotation(node.annotations());
+            .with_annotation(node.annotations(), errors);
         Ok(rule)
     }
-    fn build_group(node: &GroupStatementNode) -> Result<(Option<YggdrasilIdentifier>, Vec<Self>), YggdrasilError> {
+    /// Branches with errors are skipped
+    fn build_climb(node: &ClimbStatementNode, errors: &mut Vec<YggdrasilError>) -> Result<Self, YggdrasilError> {
+        let name = YggdrasilIdentifier::build(&node.name);
+        let mut priority = vec![];
+        for branch in &node.union_block.union_branch {
+            match YggdrasilExpression::build_tag_branch(branch) {
+                Ok(mut o) => {
+                    o.branch.replace_self(&name);
+                    priority.push(o)
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        let rule = Self { name, body: GrammarBody::Climb { priority }, range: node.get_range().unwrap_or_default(), ..Default::default() }
+            .with_annotation(node.annotations(), errors);
+        Ok(rule)
+    }
+    /// Terms with errors are skipped
+    fn build_group(node: &GroupStatementNode, errors: &mut Vec<YggdrasilError>) -> (Option<YggdrasilIdentifier>, Vec<Self>) {
         let name = node.identifier.as_ref().map(YggdrasilIdentifier::build);
         let mut out = vec![];
         for term in &node.group_block.group_pair {
             match GrammarRule::build_class_in_group(term) {
-                Ok(o) => out.push(o.with_annotation(node.annotations())),
-                Err(_) => {}
+                Ok(o) => out.push(o.with_annotation(node.annotations(), errors)),
+                Err(e) => errors.push(e),
             }
         }
-        Ok((name, out))
+        (name, out)
+    }
+}
+
+impl FunctionRule {
+    fn build(node: &MacroStatementNode) -> Result<Self, YggdrasilError> {
+        let parameters = match &node.macro_parameters {
+            Some(s) => s.identifier.iter().map(YggdrasilIdentifier::build).collect(),
+            None => vec![],
+        };
+        Ok(Self {
+            name: YggdrasilIdentifier::build(&node.name),
+            parameters,
+            body: YggdrasilExpression::build_or(&node.class_block.expression)?,
+            range: node.get_range().unwrap_or_default(),
+        })
+    }
+}
+
+impl YggdrasilMacroCall {
+    fn build(node: &FunctionCallNode) -> Result<Self, YggdrasilError> {
+        let name = YggdrasilIdentifier::build(&node.function_name.identifier);
+        let mut arguments = Vec::with_capacity(node.call_body.expression.len());
+        for e in &node.call_body.expression {
+            arguments.push(YggdrasilMacroArgument { key: None, value: YggdrasilExpression::build_or(e)? })
+        }
+        let range = node.get_range().unwrap_or_default();
+        Ok(Self { name: YggdrasilNamepath { identifiers: vec![name], range: range.clone() }, arguments, range })
     }
 }
 
@@ -172,8 +265,22 @@ impl YggdrasilExpression {
     }
     fn build_tag_branch(node: &UnionBranchNode) -> Result<YggdrasilVariant, YggdrasilError> {
         let id = node.branch_tag.as_ref().map(|o| YggdrasilIdentifier::build(&o.identifier));
+        let right = node.branch_tag.as_ref().map(|o| o.right_associativity.is_some()).unwrap_or(false);
         let expr = YggdrasilExpression::build_hard(&node.expression_hard)?;
-        Ok(YggdrasilVariant { tag: id, branch: expr })
+        Ok(YggdrasilVariant { tag: id, branch: expr, right_associative: right })
+    }
+    /// `Self` in `climb` refers to the rule itself, always boxed in the ast
+    fn replace_self(&mut self, rule: &YggdrasilIdentifier) {
+        match &mut self.body {
+            ExpressionBody::Choice(v) => v.branches.iter_mut().for_each(|e| e.replace_self(rule)),
+            ExpressionBody::Concat(v) => v.sequence.iter_mut().for_each(|e| e.replace_self(rule)),
+            ExpressionBody::Unary(v) => v.base.replace_self(rule),
+            ExpressionBody::Rule(r) if r.name.text.eq("Self") || r.name.text.eq(&rule.text) => {
+                r.name = YggdrasilIdentifier { text: rule.text.clone(), range: r.name.range.clone() };
+                r.boxed = true;
+            }
+            _ => {}
+        }
     }
     fn build_tag_node(node: &ExpressionTagNode) -> Result<Self, YggdrasilError> {
         let e = match &node.identifier {
@@ -208,43 +315,42 @@ impl YggdrasilExpression {
                 BooleanNode::False => YggdrasilExpression::boolean(false),
                 BooleanNode::True => YggdrasilExpression::boolean(true),
             },
-            AtomicNode::FunctionCall(v) => {
-                todo!()
-            }
+            AtomicNode::FunctionCall(v) => YggdrasilMacroCall::build(v)?.into(),
             AtomicNode::Identifier(v) => YggdrasilIdentifier::build(v).into(),
             AtomicNode::RegexEmbed(v) => YggdrasilRegex::new(&v.regex_inner.text, v.get_range().unwrap_or_default()).into(),
             AtomicNode::RegexRange(v) => YggdrasilRegex::new(&v.text, v.get_range().unwrap_or_default()).into(),
-            AtomicNode::String(v) => match v {
-                StringNode::Normal(s) => {
-                    let mut buffer = String::new();
-                    for s in &s.string_item {
-                        match s {
-                            StringItemNode::EscapedCharacter(item) => match item.text.chars().last() {
-                                Some(c) => match c {
-                                    'r' => buffer.push('\r'),
-                                    'n' => buffer.push('\n'),
-                                    _ => buffer.push(c),
-                                },
-                                None => unreachable!(),
-                            },
-                            StringItemNode::EscapedUnicode(_) => {
-                                unimplemented!()
-                            }
-                            StringItemNode::TextAny(s) => buffer.push_str(&s.text),
-                        }
-                    }
-                    YggdrasilText::new(buffer, Default::default()).into()
-                }
-                StringNode::Raw(s) => {
-                    YggdrasilText::new(s.text.trim_matches('\''), Default::default()).into()
-                    // YggdrasilText::new(s.text.clone(), s.get_range().unwrap_or_default()).into()
-                }
-            },
+            AtomicNode::String(v) => YggdrasilText::new(build_string(v), v.get_range().unwrap_or_default()).into(),
         };
         Ok(expr)
     }
 }
 
+fn build_string(node: &StringNode) -> String {
+    match node {
+        StringNode::Normal(s) => {
+            let mut buffer = String::new();
+            for s in &s.string_item {
+                match s {
+                    StringItemNode::EscapedCharacter(item) => match item.text.chars().last() {
+                        Some(c) => match c {
+                            'r' => buffer.push('\r'),
+                            'n' => buffer.push('\n'),
+                            _ => buffer.push(c),
+                        },
+                        None => unreachable!(),
+                    },
+                    StringItemNode::EscapedUnicode(_) => {
+                        unimplemented!()
+                    }
+                    StringItemNode::TextAny(s) => buffer.push_str(&s.text),
+                }
+            }
+            buffer
+        }
+        StringNode::Raw(s) => s.text.trim_matches('\'').to_string(),
+    }
+}
+
 impl YggdrasilIdentifier {
     fn build(node: &IdentifierNode) -> Self {
         Self { text: node.text.clone(), range: node.get_range().unwrap_or_default() }
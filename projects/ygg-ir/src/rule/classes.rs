@@ -22,6 +22,7 @@ impl BitAndAssign for FieldMap {
             match self.fields.get_mut(&key) {
                 Some(s) => {
                     s.count &= value.count;
+                    s.boxing |= value.boxing;
                     s.bind_position.extend(value.bind_position);
                     s.rule_position.extend(value.rule_position);
                 }
@@ -39,6 +40,7 @@ impl BitOrAssign for FieldMap {
             match self.fields.get_mut(&key) {
                 Some(s) => {
                     s.count |= value.count;
+                    s.boxing |= value.boxing;
                     s.bind_position.extend(value.bind_position);
                     s.rule_position.extend(value.rule_position);
                 }
@@ -56,6 +58,7 @@ impl BitXorAssign for FieldMap {
             match self.fields.get_mut(&key) {
                 Some(s) => {
                     s.count *= value.count;
+                    s.boxing |= value.boxing;
                     s.bind_position.extend(value.bind_position);
                     s.rule_position.extend(value.rule_position);
                 }
@@ -0,0 +1,79 @@
+use super::*;
+use std::slice::from_ref;
+
+/// Where the `Self` operands appear in a branch of `climb` rule
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClimbPosition {
+    /// `Atom`, `'(' Self ')'`
+    Atomic,
+    /// `'-' Self`
+    Prefix,
+    /// `Self '?'`
+    Postfix,
+    /// `Self '+' Self`
+    Infix,
+}
+
+/// A branch of `climb` rule, split into operands and operator
+#[derive(Clone, Debug)]
+pub struct ClimbOperator<'i> {
+    /// The role of this branch
+    pub position: ClimbPosition,
+    /// Binding power, the earlier branch binds tighter
+    pub priority: u32,
+    /// The left operand, `x` in `x:Self '+' y:Self`
+    pub lhs: Option<&'i YggdrasilExpression>,
+    /// Everything between the operands
+    pub operator: &'i [YggdrasilExpression],
+    /// The right operand, `y` in `x:Self '+' y:Self`
+    pub rhs: Option<&'i YggdrasilExpression>,
+    /// The original branch
+    pub variant: &'i YggdrasilVariant,
+}
+
+impl GrammarRule {
+    /// Split all branches of `climb` rule, the first branch has the highest priority
+    pub fn climb_operators(&self) -> Vec<ClimbOperator> {
+        let priority = match &self.body {
+            GrammarBody::Climb { priority } => priority,
+            _ => unreachable!("do you filter with `climb`?"),
+        };
+        let mut out = Vec::with_capacity(priority.len());
+        for (index, variant) in priority.iter().enumerate() {
+            let items = match &variant.branch.body {
+                ExpressionBody::Concat(v) if variant.branch.tag.is_none() => v.sequence.as_slice(),
+                _ => from_ref(&variant.branch),
+            };
+            let is_self = |e: &YggdrasilExpression| e.as_rule().map_or(false, |r| r.name.text.eq(&self.name.text));
+            let (lhs, rhs) = match items {
+                [head, .., last] => (is_self(head), is_self(last)),
+                _ => (false, false),
+            };
+            let (position, operator) = match (lhs, rhs) {
+                (true, true) => (ClimbPosition::Infix, &items[1..items.len() - 1]),
+                (true, false) => (ClimbPosition::Postfix, &items[1..]),
+                (false, true) => (ClimbPosition::Prefix, &items[..items.len() - 1]),
+                (false, false) => (ClimbPosition::Atomic, items),
+            };
+            out.push(ClimbOperator {
+                position,
+                priority: (priority.len() - index) as u32,
+                lhs: if lhs { items.first() } else { None },
+                operator,
+                rhs: if rhs { items.last() } else { None },
+                variant,
+            })
+        }
+        out
+    }
+}
+
+impl<'i> ClimbOperator<'i> {
+    /// The minimum binding power of the right operand
+    pub fn rhs_priority(&self) -> u32 {
+        match self.position {
+            ClimbPosition::Infix if !self.variant.right_associative => self.priority + 1,
+            _ => self.priority,
+        }
+    }
+}
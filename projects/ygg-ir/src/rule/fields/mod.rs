@@ -65,6 +65,18 @@ impl GrammarRule {
             _ => unreachable!("do you filter with `union`?"),
         }
     }
+    pub fn climb_fields(&self) -> YggdrasilEnumerate {
+        match &self.body {
+            GrammarBody::Climb { priority } => {
+                let mut variants = YggdrasilEnumerate::default();
+                for variant in priority {
+                    variants.insert(variant)
+                }
+                variants
+            }
+            _ => unreachable!("do you filter with `climb`?"),
+        }
+    }
 }
 
 impl YggdrasilExpression {
@@ -144,6 +156,9 @@ impl UnaryExpression {
     pub fn field_map(&self) -> FieldMap {
         let mut map = self.base.field_map();
         map *= self.counter();
+        if self.operators.iter().any(|o| matches!(o, YggdrasilOperator::Boxing | YggdrasilOperator::Recursive)) {
+            map.fields.values_mut().for_each(|field| field.boxing = true);
+        }
         map
     }
 
@@ -187,6 +202,7 @@ impl YggdrasilOperator {
             YggdrasilOperator::Boxing => FieldCounter::ONE,
             YggdrasilOperator::RepeatsBetween { min, max } => FieldCounter::new(*min, *max),
             YggdrasilOperator::Recursive => FieldCounter::ONE,
+            YggdrasilOperator::Label(_) | YggdrasilOperator::Error(_) => FieldCounter::ONE,
         }
     }
 }
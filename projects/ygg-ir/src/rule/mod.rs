@@ -19,6 +19,7 @@ pub use self::{
     annotations::{YggdrasilMacroArgument, YggdrasilMacroCall, YggdrasilModifiers},
     atomic::GrammarAtomic,
     classes::FieldMap,
+    climb::{ClimbOperator, ClimbPosition},
     derive::RuleDerive,
     fields::{counter::FieldCounter, YggdrasilField},
     identifier::{YggdrasilIdentifier, YggdrasilNamepath},
@@ -30,13 +31,32 @@ pub mod derive;
 
 mod annotations;
 mod atomic;
+mod climb;
 mod fields;
 mod identifier;
 mod unions;
 
+/// A rule with parameters, expanded at every call site
+///
+/// ## Examples
+/// ```ygg
+/// macro sep_by(item, sep) {
+///     (item (sep item)*)?
+/// }
+///
+/// class Array {
+///     '[' @sep_by(Item, ',') ']'
+/// }
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct FunctionRule {}
+pub struct FunctionRule {
+    pub name: YggdrasilIdentifier,
+    /// References to these names are replaced by the arguments
+    pub parameters: Vec<YggdrasilIdentifier>,
+    pub body: YggdrasilExpression,
+    pub range: Range<usize>,
+}
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -79,11 +99,11 @@ pub struct GrammarRule {
     /// atomic class Rule { }
     /// ```
     pub atomic: GrammarAtomic,
-    /// Automatically inline when this rule is called
+    /// Automatically inline when this rule is called, `_Rule` inlines a single call
     ///
     /// ## Examples
     /// ```ygg
-    /// #inline(true)
+    /// #inline()
     /// inline class Rule { }
     /// class _Rule { }
     /// ```
@@ -114,6 +134,64 @@ pub struct GrammarRule {
     /// ignore class Rule { }
     /// ```
     pub ignored: bool,
+    /// Memorize the result at each offset, avoid exponential backtracking.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// #memo()
+    /// memo class Rule { }
+    /// ```
+    pub memo: bool,
+    /// Calls itself at the same offset, directly or through other rules, filled by the optimizer.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// class Expr { Expr '+' Term | Term }
+    /// ```
+    pub left_recursive: bool,
+    /// Append the branches to the union of the base grammar, instead of overriding it.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// grammar Json5 : Json { }
+    ///
+    /// extend union Value {
+    ///     | Infinity #Infinity
+    /// }
+    /// ```
+    pub extend: bool,
+    /// Skip to these tokens when this rule fails in recovery mode, rules are looked ahead but not consumed.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// #recover(';', '}', Statement)
+    /// class Statement { }
+    /// ```
+    pub recover: Vec<YggdrasilExpression>,
+    /// Reported in errors instead of the attempts, when this rule fails without any progress.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// @label("expression")
+    /// class ExpressionHard { }
+    /// ```
+    pub label: Option<String>,
+    /// Reported in errors instead of the expected items, when the furthest failure is inside this rule.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// @error("expected a statement")
+    /// class Statement { }
+    /// ```
+    pub error: Option<String>,
+    /// Names of the highlight style, the innermost rule with a style colors the text.
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// @style(keyword)
+    /// class KW_IF { 'if' }
+    /// ```
+    pub styles: Vec<String>,
     ///
     pub captures: GrammarCaptures,
     ///
@@ -200,6 +278,13 @@ impl Default for GrammarRule {
             entry: false,
             hide: false,
             ignored: false,
+            memo: false,
+            left_recursive: false,
+            extend: false,
+            recover: vec![],
+            label: None,
+            error: None,
+            styles: vec![],
             captures: Default::default(),
             body: Default::default(),
             range: Default::default(),
@@ -219,6 +304,9 @@ impl GrammarRule {
     pub fn is_union(&self) -> bool {
         matches!(self.body, GrammarBody::Union { .. })
     }
+    pub fn is_climb(&self) -> bool {
+        matches!(self.body, GrammarBody::Climb { .. })
+    }
     pub fn node_name(&self) -> String {
         let id = match &self.redirect {
             Some(s) => s.text.as_str(),
@@ -11,6 +11,15 @@ pub struct YggdrasilEnumerate {
 pub struct YggdrasilVariant {
     pub tag: Option<YggdrasilIdentifier>,
     pub branch: YggdrasilExpression,
+    /// Only works in `climb`, the operator is right associative
+    ///
+    /// ## Examples
+    /// ```ygg
+    /// climb Expr {
+    ///     | x:Self '^' y:Self #Power >
+    /// }
+    /// ```
+    pub right_associative: bool,
 }
 
 impl IntoIterator for YggdrasilEnumerate {
@@ -24,6 +24,18 @@ impl YggdrasilParser for BootstrapParser {
     fn parse_cst(input: &str, rule: Self::Rule) -> OutputResult<BootstrapRule> {
         self::parse_cst::parse_cst(input, rule)
     }
+    fn parse_cst_recover(input: &str, rule: Self::Rule) -> (TokenTree<BootstrapRule>, Vec<YggdrasilError<BootstrapRule>>) {
+        self::parse_cst::parse_cst_recover(input, rule)
+    }
+    fn reparse_cst<'i>(
+        old: &TokenTree<BootstrapRule>,
+        edit: Range<usize>,
+        text: &str,
+        input: &'i str,
+        rule: Self::Rule,
+    ) -> OutputResult<'i, BootstrapRule> {
+        self::parse_cst::reparse_cst(old, edit, text, input, rule)
+    }
 }
 
 #[repr(u32)]
@@ -1,7 +1,28 @@
 use super::*;
 
 pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<BootstrapRule> {
-    state(input, |state| match rule {
+    state(input, |state| parse_rule(state, rule))
+}
+
+pub(super) fn parse_cst_recover(
+    input: &str,
+    rule: BootstrapRule,
+) -> (TokenTree<BootstrapRule>, Vec<YggdrasilError<BootstrapRule>>) {
+    state_recover(input, |state| parse_rule(state, rule))
+}
+
+pub(super) fn reparse_cst<'i>(
+    old: &TokenTree<BootstrapRule>,
+    edit: Range<usize>,
+    text: &str,
+    input: &'i str,
+    rule: BootstrapRule,
+) -> OutputResult<'i, BootstrapRule> {
+    state_reparse(old, edit, text, input, rule, parse_rule)
+}
+
+fn parse_rule(state: Input, rule: BootstrapRule) -> Output {
+    match rule {
         BootstrapRule::Root => parse_root(state),
         BootstrapRule::Statement => parse_statement(state),
         BootstrapRule::GrammarStatement => parse_grammar_statement(state),
@@ -60,7 +81,7 @@ pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<Bootst
         BootstrapRule::Comment => parse_comment(state),
         BootstrapRule::IgnoreText => unreachable!(),
         BootstrapRule::IgnoreRegex => unreachable!(),
-    })
+    }
 }
 #[inline]
 fn parse_root(state: Input) -> Output {
@@ -349,10 +370,14 @@ fn parse_decorator_name(state: Input) -> Output {
         s.sequence(|s| {
             Ok(s)
                 .and_then(|s| {
-                    builtin_regex(s, {
-                        static REGEX: OnceLock<Regex> = OnceLock::new();
-                        REGEX.get_or_init(|| Regex::new("^([@#])").unwrap())
-                    })
+                    builtin_regex(
+                        s,
+                        {
+                            static REGEX: OnceLock<Regex> = OnceLock::new();
+                            REGEX.get_or_init(|| Regex::new("^([@#])").unwrap())
+                        },
+                        "[@#]",
+                    )
                 })
                 .and_then(|s| builtin_ignore(s))
                 .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("identifier")))
@@ -611,10 +636,13 @@ fn parse_string(state: Input) -> Output {
 #[inline]
 fn parse_string_raw(state: Input) -> Output {
     state.rule(BootstrapRule::StringRaw, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([^']*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([^']*)").unwrap())
+            },
+            "[^']*",
+        )
     })
 }
 #[inline]
@@ -639,28 +667,37 @@ fn parse_string_item(state: Input) -> Output {
 #[inline]
 fn parse_escaped_unicode(state: Input) -> Output {
     state.rule(BootstrapRule::EscapedUnicode, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\\\\\\\u[0-9a-zA-Z]{4})").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\\\\\\\u[0-9a-zA-Z]{4})").unwrap())
+            },
+            "\\\\\\\\u[0-9a-zA-Z]{4}",
+        )
     })
 }
 #[inline]
 fn parse_escaped_character(state: Input) -> Output {
     state.rule(BootstrapRule::EscapedCharacter, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
+            },
+            "\\\\.",
+        )
     })
 }
 #[inline]
 fn parse_text_any(state: Input) -> Output {
     state.rule(BootstrapRule::TextAny, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([^\\\"\\\\]+)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([^\\\"\\\\]+)").unwrap())
+            },
+            "[^\\\"\\\\]+",
+        )
     })
 }
 #[inline]
@@ -682,16 +719,24 @@ fn parse_regex_inner(state: Input) -> Output {
                 Ok(s).and_then(|s| builtin_ignore(s)).and_then(|s| {
                     Err(s)
                         .or_else(|s| {
-                            builtin_regex(s, {
-                                static REGEX: OnceLock<Regex> = OnceLock::new();
-                                REGEX.get_or_init(|| Regex::new("^([^\\/\\\\])").unwrap())
-                            })
+                            builtin_regex(
+                                s,
+                                {
+                                    static REGEX: OnceLock<Regex> = OnceLock::new();
+                                    REGEX.get_or_init(|| Regex::new("^([^\\/\\\\])").unwrap())
+                                },
+                                "[^\\/\\\\]",
+                            )
                         })
                         .or_else(|s| {
-                            builtin_regex(s, {
-                                static REGEX: OnceLock<Regex> = OnceLock::new();
-                                REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
-                            })
+                            builtin_regex(
+                                s,
+                                {
+                                    static REGEX: OnceLock<Regex> = OnceLock::new();
+                                    REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
+                                },
+                                "\\\\.",
+                            )
                         })
                 })
             })
@@ -775,10 +820,13 @@ fn parse_namepath(state: Input) -> Output {
 #[inline]
 fn parse_identifier(state: Input) -> Output {
     state.rule(BootstrapRule::Identifier, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([_\\p{XID_start}]\\p{XID_continue}*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([_\\p{XID_start}]\\p{XID_continue}*)").unwrap())
+            },
+            "[_\\p{XID_start}]\\p{XID_continue}*",
+        )
     })
 }
 #[inline]
@@ -792,10 +840,13 @@ fn parse_boolean(state: Input) -> Output {
 #[inline]
 fn parse_integer(state: Input) -> Output {
     state.rule(BootstrapRule::Integer, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(0|[1-9][0-9]*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(0|[1-9][0-9]*)").unwrap())
+            },
+            "0|[1-9][0-9]*",
+        )
     })
 }
 #[inline]
@@ -844,82 +895,109 @@ fn parse_modifier_call(state: Input) -> Output {
 #[inline]
 fn parse_kw_grammar(state: Input) -> Output {
     state.rule(BootstrapRule::KW_GRAMMAR, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(grammar)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(grammar)").unwrap())
+            },
+            "grammar",
+        )
     })
 }
 #[inline]
 fn parse_kw_import(state: Input) -> Output {
     state.rule(BootstrapRule::KW_IMPORT, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(using|import|use)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(using|import|use)").unwrap())
+            },
+            "using|import|use",
+        )
     })
 }
 #[inline]
 fn parse_kw_class(state: Input) -> Output {
     state.rule(BootstrapRule::KW_CLASS, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(class|struct)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(class|struct)").unwrap())
+            },
+            "class|struct",
+        )
     })
 }
 #[inline]
 fn parse_kw_union(state: Input) -> Output {
     state.rule(BootstrapRule::KW_UNION, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(union|enum)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(union|enum)").unwrap())
+            },
+            "union|enum",
+        )
     })
 }
 #[inline]
 fn parse_kw_group(state: Input) -> Output {
     state.rule(BootstrapRule::KW_GROUP, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(group|token)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(group|token)").unwrap())
+            },
+            "group|token",
+        )
     })
 }
 #[inline]
 fn parse_kw_climb(state: Input) -> Output {
     state.rule(BootstrapRule::KW_CLIMB, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(climb)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(climb)").unwrap())
+            },
+            "climb",
+        )
     })
 }
 #[inline]
 fn parse_kw_macro(state: Input) -> Output {
     state.rule(BootstrapRule::KW_MACRO, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(macro|def|function|func|fun|fn)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(macro|def|function|func|fun|fn)").unwrap())
+            },
+            "macro|def|function|func|fun|fn",
+        )
     })
 }
 #[inline]
 fn parse_white_space(state: Input) -> Output {
     state.rule(BootstrapRule::WhiteSpace, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\p{White_Space}+)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\p{White_Space}+)").unwrap())
+            },
+            "\\p{White_Space}+",
+        )
     })
 }
 #[inline]
 fn parse_comment(state: Input) -> Output {
     state.rule(BootstrapRule::Comment, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\/\\/[^\\n\\r]*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\/\\/[^\\n\\r]*)").unwrap())
+            },
+            "\\/\\/[^\\n\\r]*",
+        )
     })
 }
 
@@ -936,6 +1014,6 @@ fn builtin_text<'i>(state: Input<'i>, text: &'static str, case: bool) -> Output<
     state.rule(BootstrapRule::IgnoreText, |s| s.match_string(text, case))
 }
 
-fn builtin_regex<'i, 'r>(state: Input<'i>, regex: &'r Regex) -> Output<'i> {
-    state.rule(BootstrapRule::IgnoreRegex, |s| s.match_regex(regex))
+fn builtin_regex<'i, 'r>(state: Input<'i>, regex: &'r Regex, raw: &str) -> Output<'i> {
+    state.rule(BootstrapRule::IgnoreRegex, |s| s.match_regex(regex, raw))
 }
@@ -24,6 +24,18 @@ impl YggdrasilParser for BootstrapParser {
     fn parse_cst(input: &str, rule: Self::Rule) -> OutputResult<BootstrapRule> {
         self::parse_cst::parse_cst(input, rule)
     }
+    fn parse_cst_recover(input: &str, rule: Self::Rule) -> (TokenTree<BootstrapRule>, Vec<YggdrasilError<BootstrapRule>>) {
+        self::parse_cst::parse_cst_recover(input, rule)
+    }
+    fn reparse_cst<'i>(
+        old: &TokenTree<BootstrapRule>,
+        edit: Range<usize>,
+        text: &str,
+        input: &'i str,
+        rule: Self::Rule,
+    ) -> OutputResult<'i, BootstrapRule> {
+        self::parse_cst::reparse_cst(old, edit, text, input, rule)
+    }
 }
 
 #[repr(u32)]
@@ -34,6 +46,9 @@ pub enum BootstrapRule {
     Statement,
     GrammarStatement,
     GrammarBlock,
+    ImportStatement,
+    ImportBlock,
+    ImportItem,
     ClassStatement,
     ClassBlock,
     OP_REMARK,
@@ -42,9 +57,12 @@ pub enum BootstrapRule {
     UnionBranch,
     BranchTag,
     RightAssociativity,
+    ClimbStatement,
     GroupStatement,
     GroupBlock,
     GroupPair,
+    MacroStatement,
+    MacroParameters,
     DecoratorCall,
     DecoratorName,
     FunctionCall,
@@ -77,6 +95,7 @@ pub enum BootstrapRule {
     ModifierCall,
     KW_GRAMMAR,
     KW_IMPORT,
+    KW_AS,
     KW_CLASS,
     KW_UNION,
     KW_GROUP,
@@ -95,12 +114,19 @@ impl YggdrasilRule for BootstrapRule {
         matches!(self, Self::IgnoreText | Self::IgnoreRegex | Self::WhiteSpace | Self::Comment)
     }
 
+    fn is_literal(&self) -> bool {
+        matches!(self, Self::IgnoreText | Self::IgnoreRegex)
+    }
+
     fn get_style(&self) -> &'static str {
         match self {
             Self::Root => "",
             Self::Statement => "",
             Self::GrammarStatement => "",
             Self::GrammarBlock => "",
+            Self::ImportStatement => "",
+            Self::ImportBlock => "",
+            Self::ImportItem => "",
             Self::ClassStatement => "",
             Self::ClassBlock => "",
             Self::OP_REMARK => "",
@@ -109,9 +135,12 @@ impl YggdrasilRule for BootstrapRule {
             Self::UnionBranch => "",
             Self::BranchTag => "",
             Self::RightAssociativity => "",
+            Self::ClimbStatement => "",
             Self::GroupStatement => "",
             Self::GroupBlock => "",
             Self::GroupPair => "",
+            Self::MacroStatement => "",
+            Self::MacroParameters => "",
             Self::DecoratorCall => "",
             Self::DecoratorName => "",
             Self::FunctionCall => "",
@@ -144,6 +173,7 @@ impl YggdrasilRule for BootstrapRule {
             Self::ModifierCall => "",
             Self::KW_GRAMMAR => "",
             Self::KW_IMPORT => "",
+            Self::KW_AS => "",
             Self::KW_CLASS => "",
             Self::KW_UNION => "",
             Self::KW_GROUP => "",
@@ -165,8 +195,11 @@ pub struct RootNode {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatementNode {
     ClassStatement(ClassStatementNode),
+    ClimbStatement(ClimbStatementNode),
     GrammarStatement(GrammarStatementNode),
     GroupStatement(GroupStatementNode),
+    ImportStatement(ImportStatementNode),
+    MacroStatement(MacroStatementNode),
     UnionStatement(UnionStatementNode),
 }
 #[derive(Clone, Debug, Hash)]
@@ -174,11 +207,33 @@ pub enum StatementNode {
 pub struct GrammarStatementNode {
     pub grammar_block: GrammarBlockNode,
     pub identifier: IdentifierNode,
+    pub base: Option<IdentifierNode>,
     pub span: Range<u32>,
 }
 #[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GrammarBlockNode {
+    pub identifier: Vec<IdentifierNode>,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportStatementNode {
+    pub import_block: Option<ImportBlockNode>,
+    pub path: StringNode,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportBlockNode {
+    pub import_item: Vec<ImportItemNode>,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ImportItemNode {
+    pub alias: Option<IdentifierNode>,
+    pub name: IdentifierNode,
     pub span: Range<u32>,
 }
 #[derive(Clone, Debug, Hash)]
@@ -240,6 +295,16 @@ pub struct RightAssociativityNode {
 }
 #[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClimbStatementNode {
+    pub decorator_call: Vec<DecoratorCallNode>,
+    pub modifier_call: Vec<ModifierCallNode>,
+    pub op_remark: Option<OpRemarkNode>,
+    pub union_block: UnionBlockNode,
+    pub name: IdentifierNode,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupStatementNode {
     pub decorator_call: Vec<DecoratorCallNode>,
     pub group_block: GroupBlockNode,
@@ -262,6 +327,22 @@ pub struct GroupPairNode {
 }
 #[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MacroStatementNode {
+    pub class_block: ClassBlockNode,
+    pub decorator_call: Vec<DecoratorCallNode>,
+    pub macro_parameters: Option<MacroParametersNode>,
+    pub modifier_call: Vec<ModifierCallNode>,
+    pub name: IdentifierNode,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MacroParametersNode {
+    pub identifier: Vec<IdentifierNode>,
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DecoratorCallNode {
     pub call_body: CallBodyNode,
     pub decorator_name: DecoratorNameNode,
@@ -470,6 +551,11 @@ pub struct KwImportNode {
 }
 #[derive(Clone, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KwAsNode {
+    pub span: Range<u32>,
+}
+#[derive(Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct KwClassNode {
     pub span: Range<u32>,
 }
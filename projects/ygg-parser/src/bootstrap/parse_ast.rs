@@ -29,8 +29,11 @@ impl YggdrasilNode for StatementNode {
     fn get_range(&self) -> Option<Range<usize>> {
         match self {
             Self::ClassStatement(s) => s.get_range(),
+            Self::ClimbStatement(s) => s.get_range(),
             Self::GrammarStatement(s) => s.get_range(),
             Self::GroupStatement(s) => s.get_range(),
+            Self::ImportStatement(s) => s.get_range(),
+            Self::MacroStatement(s) => s.get_range(),
             Self::UnionStatement(s) => s.get_range(),
         }
     }
@@ -39,12 +42,21 @@ impl YggdrasilNode for StatementNode {
         if let Ok(s) = pair.take_tagged_one::<ClassStatementNode>(Cow::Borrowed("class_statement")) {
             return Ok(Self::ClassStatement(s));
         }
+        if let Ok(s) = pair.take_tagged_one::<ClimbStatementNode>(Cow::Borrowed("climb_statement")) {
+            return Ok(Self::ClimbStatement(s));
+        }
         if let Ok(s) = pair.take_tagged_one::<GrammarStatementNode>(Cow::Borrowed("grammar_statement")) {
             return Ok(Self::GrammarStatement(s));
         }
         if let Ok(s) = pair.take_tagged_one::<GroupStatementNode>(Cow::Borrowed("group_statement")) {
             return Ok(Self::GroupStatement(s));
         }
+        if let Ok(s) = pair.take_tagged_one::<ImportStatementNode>(Cow::Borrowed("import_statement")) {
+            return Ok(Self::ImportStatement(s));
+        }
+        if let Ok(s) = pair.take_tagged_one::<MacroStatementNode>(Cow::Borrowed("macro_statement")) {
+            return Ok(Self::MacroStatement(s));
+        }
         if let Ok(s) = pair.take_tagged_one::<UnionStatementNode>(Cow::Borrowed("union_statement")) {
             return Ok(Self::UnionStatement(s));
         }
@@ -71,6 +83,7 @@ impl YggdrasilNode for GrammarStatementNode {
         Ok(Self {
             grammar_block: pair.take_tagged_one::<GrammarBlockNode>(Cow::Borrowed("grammar_block"))?,
             identifier: pair.take_tagged_one::<IdentifierNode>(Cow::Borrowed("identifier"))?,
+            base: pair.take_tagged_option::<IdentifierNode>(Cow::Borrowed("base")),
             span: Range { start: _span.start() as u32, end: _span.end() as u32 },
         })
     }
@@ -92,7 +105,10 @@ impl YggdrasilNode for GrammarBlockNode {
     }
     fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
         let _span = pair.get_span();
-        Ok(Self { span: Range { start: _span.start() as u32, end: _span.end() as u32 } })
+        Ok(Self {
+            identifier: pair.take_tagged_items::<IdentifierNode>(Cow::Borrowed("identifier")).collect::<Result<Vec<_>, _>>()?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
     }
 }
 #[automatically_derived]
@@ -104,6 +120,77 @@ impl FromStr for GrammarBlockNode {
     }
 }
 #[automatically_derived]
+impl YggdrasilNode for ImportStatementNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            import_block: pair.take_tagged_option::<ImportBlockNode>(Cow::Borrowed("import_block")),
+            path: pair.take_tagged_one::<StringNode>(Cow::Borrowed("path"))?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for ImportStatementNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::ImportStatement)?)
+    }
+}
+#[automatically_derived]
+impl YggdrasilNode for ImportBlockNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            import_item: pair.take_tagged_items::<ImportItemNode>(Cow::Borrowed("import_item")).collect::<Result<Vec<_>, _>>()?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for ImportBlockNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::ImportBlock)?)
+    }
+}
+#[automatically_derived]
+impl YggdrasilNode for ImportItemNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            alias: pair.take_tagged_option::<IdentifierNode>(Cow::Borrowed("alias")),
+            name: pair.take_tagged_one::<IdentifierNode>(Cow::Borrowed("name"))?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for ImportItemNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::ImportItem)?)
+    }
+}
+#[automatically_derived]
 impl YggdrasilNode for ClassStatementNode {
     type Rule = BootstrapRule;
 
@@ -303,6 +390,37 @@ impl FromStr for RightAssociativityNode {
     }
 }
 #[automatically_derived]
+impl YggdrasilNode for ClimbStatementNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            decorator_call: pair
+                .take_tagged_items::<DecoratorCallNode>(Cow::Borrowed("decorator_call"))
+                .collect::<Result<Vec<_>, _>>()?,
+            modifier_call: pair
+                .take_tagged_items::<ModifierCallNode>(Cow::Borrowed("modifier_call"))
+                .collect::<Result<Vec<_>, _>>()?,
+            op_remark: pair.take_tagged_option::<OpRemarkNode>(Cow::Borrowed("op_remark")),
+            union_block: pair.take_tagged_one::<UnionBlockNode>(Cow::Borrowed("union_block"))?,
+            name: pair.take_tagged_one::<IdentifierNode>(Cow::Borrowed("name"))?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for ClimbStatementNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::ClimbStatement)?)
+    }
+}
+#[automatically_derived]
 impl YggdrasilNode for GroupStatementNode {
     type Rule = BootstrapRule;
 
@@ -380,6 +498,60 @@ impl FromStr for GroupPairNode {
     }
 }
 #[automatically_derived]
+impl YggdrasilNode for MacroStatementNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            class_block: pair.take_tagged_one::<ClassBlockNode>(Cow::Borrowed("class_block"))?,
+            decorator_call: pair
+                .take_tagged_items::<DecoratorCallNode>(Cow::Borrowed("decorator_call"))
+                .collect::<Result<Vec<_>, _>>()?,
+            macro_parameters: pair.take_tagged_option::<MacroParametersNode>(Cow::Borrowed("macro_parameters")),
+            modifier_call: pair
+                .take_tagged_items::<ModifierCallNode>(Cow::Borrowed("modifier_call"))
+                .collect::<Result<Vec<_>, _>>()?,
+            name: pair.take_tagged_one::<IdentifierNode>(Cow::Borrowed("name"))?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for MacroStatementNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::MacroStatement)?)
+    }
+}
+#[automatically_derived]
+impl YggdrasilNode for MacroParametersNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self {
+            identifier: pair.take_tagged_items::<IdentifierNode>(Cow::Borrowed("identifier")).collect::<Result<Vec<_>, _>>()?,
+            span: Range { start: _span.start() as u32, end: _span.end() as u32 },
+        })
+    }
+}
+#[automatically_derived]
+impl FromStr for MacroParametersNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::MacroParameters)?)
+    }
+}
+#[automatically_derived]
 impl YggdrasilNode for DecoratorCallNode {
     type Rule = BootstrapRule;
 
@@ -770,10 +942,10 @@ impl YggdrasilNode for StringNode {
     }
     fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
         let _span = pair.get_span();
-        if let Ok(s) = pair.take_tagged_one::<StringNormalNode>(Cow::Borrowed("normal")) {
+        if let Ok(s) = pair.take_tagged_one::<StringNormalNode>(Cow::Borrowed("string_normal")) {
             return Ok(Self::Normal(s));
         }
-        if let Ok(s) = pair.take_tagged_one::<StringRawNode>(Cow::Borrowed("raw")) {
+        if let Ok(s) = pair.take_tagged_one::<StringRawNode>(Cow::Borrowed("string_raw")) {
             return Ok(Self::Raw(s));
         }
         Err(YggdrasilError::invalid_node(BootstrapRule::String, _span))
@@ -1181,6 +1353,26 @@ impl FromStr for KwImportNode {
     }
 }
 #[automatically_derived]
+impl YggdrasilNode for KwAsNode {
+    type Rule = BootstrapRule;
+
+    fn get_range(&self) -> Option<Range<usize>> {
+        Some(Range { start: self.span.start as usize, end: self.span.end as usize })
+    }
+    fn from_pair(pair: TokenPair<Self::Rule>) -> Result<Self, YggdrasilError<Self::Rule>> {
+        let _span = pair.get_span();
+        Ok(Self { span: Range { start: _span.start() as u32, end: _span.end() as u32 } })
+    }
+}
+#[automatically_derived]
+impl FromStr for KwAsNode {
+    type Err = YggdrasilError<BootstrapRule>;
+
+    fn from_str(input: &str) -> Result<Self, YggdrasilError<BootstrapRule>> {
+        Self::from_cst(BootstrapParser::parse_cst(input, BootstrapRule::KW_AS)?)
+    }
+}
+#[automatically_derived]
 impl YggdrasilNode for KwClassNode {
     type Rule = BootstrapRule;
 
@@ -1,11 +1,35 @@
 use super::*;
 
 pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<BootstrapRule> {
-    state(input, |state| match rule {
+    state(input, |state| parse_rule(state, rule))
+}
+
+pub(super) fn parse_cst_recover(
+    input: &str,
+    rule: BootstrapRule,
+) -> (TokenTree<BootstrapRule>, Vec<YggdrasilError<BootstrapRule>>) {
+    state_recover(input, |state| parse_rule(state, rule))
+}
+
+pub(super) fn reparse_cst<'i>(
+    old: &TokenTree<BootstrapRule>,
+    edit: Range<usize>,
+    text: &str,
+    input: &'i str,
+    rule: BootstrapRule,
+) -> OutputResult<'i, BootstrapRule> {
+    state_reparse(old, edit, text, input, rule, parse_rule)
+}
+
+fn parse_rule(state: Input, rule: BootstrapRule) -> Output {
+    match rule {
         BootstrapRule::Root => parse_root(state),
         BootstrapRule::Statement => parse_statement(state),
         BootstrapRule::GrammarStatement => parse_grammar_statement(state),
         BootstrapRule::GrammarBlock => parse_grammar_block(state),
+        BootstrapRule::ImportStatement => parse_import_statement(state),
+        BootstrapRule::ImportBlock => parse_import_block(state),
+        BootstrapRule::ImportItem => parse_import_item(state),
         BootstrapRule::ClassStatement => parse_class_statement(state),
         BootstrapRule::ClassBlock => parse_class_block(state),
         BootstrapRule::OP_REMARK => parse_op_remark(state),
@@ -14,9 +38,12 @@ pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<Bootst
         BootstrapRule::UnionBranch => parse_union_branch(state),
         BootstrapRule::BranchTag => parse_branch_tag(state),
         BootstrapRule::RightAssociativity => parse_right_associativity(state),
+        BootstrapRule::ClimbStatement => parse_climb_statement(state),
         BootstrapRule::GroupStatement => parse_group_statement(state),
         BootstrapRule::GroupBlock => parse_group_block(state),
         BootstrapRule::GroupPair => parse_group_pair(state),
+        BootstrapRule::MacroStatement => parse_macro_statement(state),
+        BootstrapRule::MacroParameters => parse_macro_parameters(state),
         BootstrapRule::DecoratorCall => parse_decorator_call(state),
         BootstrapRule::DecoratorName => parse_decorator_name(state),
         BootstrapRule::FunctionCall => parse_function_call(state),
@@ -49,6 +76,7 @@ pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<Bootst
         BootstrapRule::ModifierCall => parse_modifier_call(state),
         BootstrapRule::KW_GRAMMAR => parse_kw_grammar(state),
         BootstrapRule::KW_IMPORT => parse_kw_import(state),
+        BootstrapRule::KW_AS => parse_kw_as(state),
         BootstrapRule::KW_CLASS => parse_kw_class(state),
         BootstrapRule::KW_UNION => parse_kw_union(state),
         BootstrapRule::KW_GROUP => parse_kw_group(state),
@@ -58,7 +86,7 @@ pub(super) fn parse_cst(input: &str, rule: BootstrapRule) -> OutputResult<Bootst
         BootstrapRule::Comment => parse_comment(state),
         BootstrapRule::IgnoreText => unreachable!(),
         BootstrapRule::IgnoreRegex => unreachable!(),
-    })
+    }
 }
 #[inline]
 fn parse_root(state: Input) -> Output {
@@ -75,9 +103,12 @@ fn parse_statement(state: Input) -> Output {
     state.rule(BootstrapRule::Statement, |s| {
         Err(s)
             .or_else(|s| parse_grammar_statement(s).and_then(|s| s.tag_node("grammar_statement")))
+            .or_else(|s| parse_import_statement(s).and_then(|s| s.tag_node("import_statement")))
             .or_else(|s| parse_class_statement(s).and_then(|s| s.tag_node("class_statement")))
             .or_else(|s| parse_union_statement(s).and_then(|s| s.tag_node("union_statement")))
+            .or_else(|s| parse_climb_statement(s).and_then(|s| s.tag_node("climb_statement")))
             .or_else(|s| parse_group_statement(s).and_then(|s| s.tag_node("group_statement")))
+            .or_else(|s| parse_macro_statement(s).and_then(|s| s.tag_node("macro_statement")))
     })
 }
 #[inline]
@@ -89,6 +120,17 @@ fn parse_grammar_statement(state: Input) -> Output {
                 .and_then(|s| builtin_ignore(s))
                 .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("identifier")))
                 .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.optional(|s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| builtin_text(s, ":", false))
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("base")))
+                        })
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
                 .and_then(|s| parse_grammar_block(s).and_then(|s| s.tag_node("grammar_block")))
         })
     })
@@ -100,11 +142,98 @@ fn parse_grammar_block(state: Input) -> Output {
             Ok(s)
                 .and_then(|s| builtin_text(s, "{", false))
                 .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.repeat(0..4294967295, |s| {
+                        s.sequence(|s| {
+                            Ok(s).and_then(|s| builtin_ignore(s)).and_then(|s| {
+                                s.sequence(|s| {
+                                    Ok(s)
+                                        .and_then(|s| builtin_text(s, "-", false))
+                                        .and_then(|s| builtin_ignore(s))
+                                        .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("identifier")))
+                                })
+                            })
+                        })
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| builtin_text(s, "}", false))
+        })
+    })
+}
+#[inline]
+fn parse_import_statement(state: Input) -> Output {
+    state.rule(BootstrapRule::ImportStatement, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| parse_kw_import(s))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| parse_string(s).and_then(|s| s.tag_node("path")))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| s.optional(|s| parse_import_block(s).and_then(|s| s.tag_node("import_block"))))
+        })
+    })
+}
+#[inline]
+fn parse_import_block(state: Input) -> Output {
+    state.rule(BootstrapRule::ImportBlock, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| builtin_text(s, "{", false))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.optional(|s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| parse_import_item(s).and_then(|s| s.tag_node("import_item")))
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| {
+                                    s.repeat(0..4294967295, |s| {
+                                        s.sequence(|s| {
+                                            Ok(s).and_then(|s| builtin_ignore(s)).and_then(|s| {
+                                                s.sequence(|s| {
+                                                    Ok(s)
+                                                        .and_then(|s| builtin_text(s, ",", false))
+                                                        .and_then(|s| builtin_ignore(s))
+                                                        .and_then(|s| {
+                                                            parse_import_item(s).and_then(|s| s.tag_node("import_item"))
+                                                        })
+                                                })
+                                            })
+                                        })
+                                    })
+                                })
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| s.optional(|s| builtin_text(s, ",", false)))
+                        })
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
                 .and_then(|s| builtin_text(s, "}", false))
         })
     })
 }
 #[inline]
+fn parse_import_item(state: Input) -> Output {
+    state.rule(BootstrapRule::ImportItem, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("name")))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.optional(|s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| parse_kw_as(s))
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("alias")))
+                        })
+                    })
+                })
+        })
+    })
+}
+#[inline]
 fn parse_class_statement(state: Input) -> Output {
     state.rule(BootstrapRule::ClassStatement, |s| {
         s.sequence(|s| {
@@ -264,6 +393,46 @@ fn parse_right_associativity(state: Input) -> Output {
     state.rule(BootstrapRule::RightAssociativity, |s| s.match_string(">", false))
 }
 #[inline]
+fn parse_climb_statement(state: Input) -> Output {
+    state.rule(BootstrapRule::ClimbStatement, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| {
+                    s.sequence(|s| {
+                        Ok(s)
+                            .and_then(|s| {
+                                s.repeat(0..4294967295, |s| {
+                                    s.sequence(|s| {
+                                        Ok(s)
+                                            .and_then(|s| builtin_ignore(s))
+                                            .and_then(|s| parse_decorator_call(s).and_then(|s| s.tag_node("decorator_call")))
+                                    })
+                                })
+                            })
+                            .and_then(|s| builtin_ignore(s))
+                            .and_then(|s| {
+                                s.repeat(0..4294967295, |s| {
+                                    s.sequence(|s| {
+                                        Ok(s)
+                                            .and_then(|s| builtin_ignore(s))
+                                            .and_then(|s| parse_modifier_call(s).and_then(|s| s.tag_node("modifier_call")))
+                                    })
+                                })
+                            })
+                            .and_then(|s| builtin_ignore(s))
+                            .and_then(|s| parse_kw_climb(s))
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("name")))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| s.optional(|s| parse_op_remark(s).and_then(|s| s.tag_node("op_remark"))))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| parse_union_block(s).and_then(|s| s.tag_node("union_block")))
+        })
+    })
+}
+#[inline]
 fn parse_group_statement(state: Input) -> Output {
     state.rule(BootstrapRule::GroupStatement, |s| {
         s.sequence(|s| {
@@ -331,6 +500,85 @@ fn parse_group_pair(state: Input) -> Output {
     })
 }
 #[inline]
+fn parse_macro_statement(state: Input) -> Output {
+    state.rule(BootstrapRule::MacroStatement, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| {
+                    s.sequence(|s| {
+                        Ok(s)
+                            .and_then(|s| {
+                                s.repeat(0..4294967295, |s| {
+                                    s.sequence(|s| {
+                                        Ok(s)
+                                            .and_then(|s| builtin_ignore(s))
+                                            .and_then(|s| parse_decorator_call(s).and_then(|s| s.tag_node("decorator_call")))
+                                    })
+                                })
+                            })
+                            .and_then(|s| builtin_ignore(s))
+                            .and_then(|s| {
+                                s.repeat(0..4294967295, |s| {
+                                    s.sequence(|s| {
+                                        Ok(s)
+                                            .and_then(|s| builtin_ignore(s))
+                                            .and_then(|s| parse_modifier_call(s).and_then(|s| s.tag_node("modifier_call")))
+                                    })
+                                })
+                            })
+                            .and_then(|s| builtin_ignore(s))
+                            .and_then(|s| parse_kw_macro(s))
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("name")))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| s.optional(|s| parse_macro_parameters(s).and_then(|s| s.tag_node("macro_parameters"))))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| parse_class_block(s).and_then(|s| s.tag_node("class_block")))
+        })
+    })
+}
+#[inline]
+fn parse_macro_parameters(state: Input) -> Output {
+    state.rule(BootstrapRule::MacroParameters, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| builtin_text(s, "(", false))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.optional(|s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("identifier")))
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| {
+                                    s.repeat(0..4294967295, |s| {
+                                        s.sequence(|s| {
+                                            Ok(s).and_then(|s| builtin_ignore(s)).and_then(|s| {
+                                                s.sequence(|s| {
+                                                    Ok(s)
+                                                        .and_then(|s| builtin_text(s, ",", false))
+                                                        .and_then(|s| builtin_ignore(s))
+                                                        .and_then(|s| {
+                                                            parse_identifier(s).and_then(|s| s.tag_node("identifier"))
+                                                        })
+                                                })
+                                            })
+                                        })
+                                    })
+                                })
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| s.optional(|s| builtin_text(s, ",", false)))
+                        })
+                    })
+                })
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| builtin_text(s, ")", false))
+        })
+    })
+}
+#[inline]
 fn parse_decorator_call(state: Input) -> Output {
     state.rule(BootstrapRule::DecoratorCall, |s| {
         s.sequence(|s| {
@@ -347,10 +595,14 @@ fn parse_decorator_name(state: Input) -> Output {
         s.sequence(|s| {
             Ok(s)
                 .and_then(|s| {
-                    builtin_regex(s, {
-                        static REGEX: OnceLock<Regex> = OnceLock::new();
-                        REGEX.get_or_init(|| Regex::new("^([@#])").unwrap())
-                    })
+                    builtin_regex(
+                        s,
+                        {
+                            static REGEX: OnceLock<Regex> = OnceLock::new();
+                            REGEX.get_or_init(|| Regex::new("^([@#])").unwrap())
+                        },
+                        "[@#]",
+                    )
                 })
                 .and_then(|s| builtin_ignore(s))
                 .and_then(|s| parse_identifier(s).and_then(|s| s.tag_node("identifier")))
@@ -607,10 +859,13 @@ fn parse_string(state: Input) -> Output {
 #[inline]
 fn parse_string_raw(state: Input) -> Output {
     state.rule(BootstrapRule::StringRaw, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([^']*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([^']*)").unwrap())
+            },
+            "[^']*",
+        )
     })
 }
 
@@ -637,30 +892,39 @@ fn parse_string_item(state: Input) -> Output {
 #[inline]
 fn parse_escaped_unicode(state: Input) -> Output {
     state.rule(BootstrapRule::EscapedUnicode, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\\\\\\\u[0-9a-zA-Z]{4})").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\\\\\\\u[0-9a-zA-Z]{4})").unwrap())
+            },
+            "\\\\\\\\u[0-9a-zA-Z]{4}",
+        )
     })
 }
 
 #[inline]
 fn parse_escaped_character(state: Input) -> Output {
     state.rule(BootstrapRule::EscapedCharacter, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
+            },
+            "\\\\.",
+        )
     })
 }
 
 #[inline]
 fn parse_text_any(state: Input) -> Output {
     state.rule(BootstrapRule::TextAny, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([^\\\"\\\\]+)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([^\\\"\\\\]+)").unwrap())
+            },
+            "[^\\\"\\\\]+",
+        )
     })
 }
 #[inline]
@@ -683,16 +947,24 @@ fn parse_regex_inner(state: Input) -> Output {
                 Ok(s).and_then(|s| builtin_ignore(s)).and_then(|s| {
                     Err(s)
                         .or_else(|s| {
-                            builtin_regex(s, {
-                                static REGEX: OnceLock<Regex> = OnceLock::new();
-                                REGEX.get_or_init(|| Regex::new("^([^\\/\\\\])").unwrap())
-                            })
+                            builtin_regex(
+                                s,
+                                {
+                                    static REGEX: OnceLock<Regex> = OnceLock::new();
+                                    REGEX.get_or_init(|| Regex::new("^([^\\/\\\\])").unwrap())
+                                },
+                                "[^\\/\\\\]",
+                            )
                         })
                         .or_else(|s| {
-                            builtin_regex(s, {
-                                static REGEX: OnceLock<Regex> = OnceLock::new();
-                                REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
-                            })
+                            builtin_regex(
+                                s,
+                                {
+                                    static REGEX: OnceLock<Regex> = OnceLock::new();
+                                    REGEX.get_or_init(|| Regex::new("^(\\\\.)").unwrap())
+                                },
+                                "\\\\.",
+                            )
                         })
                 })
             })
@@ -776,10 +1048,13 @@ fn parse_namepath(state: Input) -> Output {
 #[inline]
 fn parse_identifier(state: Input) -> Output {
     state.rule(BootstrapRule::Identifier, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^([_\\p{XID_start}]\\p{XID_continue}*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^([_\\p{XID_start}]\\p{XID_continue}*)").unwrap())
+            },
+            "[_\\p{XID_start}]\\p{XID_continue}*",
+        )
     })
 }
 #[inline]
@@ -812,83 +1087,122 @@ fn parse_modifier_call(state: Input) -> Output {
 #[inline]
 fn parse_kw_grammar(state: Input) -> Output {
     state.rule(BootstrapRule::KW_GRAMMAR, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(grammar)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(grammar)").unwrap())
+            },
+            "grammar",
+        )
     })
 }
 #[inline]
 fn parse_kw_import(state: Input) -> Output {
     state.rule(BootstrapRule::KW_IMPORT, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(using|import|use)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(using|import|use)").unwrap())
+            },
+            "using|import|use",
+        )
+    })
+}
+#[inline]
+fn parse_kw_as(state: Input) -> Output {
+    state.rule(BootstrapRule::KW_AS, |s| {
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(as)").unwrap())
+            },
+            "as",
+        )
     })
 }
 #[inline]
 fn parse_kw_class(state: Input) -> Output {
     state.rule(BootstrapRule::KW_CLASS, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(class|struct)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(class|struct)").unwrap())
+            },
+            "class|struct",
+        )
     })
 }
 #[inline]
 fn parse_kw_union(state: Input) -> Output {
     state.rule(BootstrapRule::KW_UNION, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(union|enum)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(union|enum)").unwrap())
+            },
+            "union|enum",
+        )
     })
 }
 #[inline]
 fn parse_kw_group(state: Input) -> Output {
     state.rule(BootstrapRule::KW_GROUP, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(group|token)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(group|token)").unwrap())
+            },
+            "group|token",
+        )
     })
 }
 #[inline]
 fn parse_kw_climb(state: Input) -> Output {
     state.rule(BootstrapRule::KW_CLIMB, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(climb)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(climb)").unwrap())
+            },
+            "climb",
+        )
     })
 }
 #[inline]
 fn parse_kw_macro(state: Input) -> Output {
     state.rule(BootstrapRule::KW_MACRO, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(macro|def|function|func|fun|fn)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(macro|def|function|func|fun|fn)").unwrap())
+            },
+            "macro|def|function|func|fun|fn",
+        )
     })
 }
 #[inline]
 fn parse_white_space(state: Input) -> Output {
     state.rule(BootstrapRule::WhiteSpace, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\p{White_Space}+)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\p{White_Space}+)").unwrap())
+            },
+            "\\p{White_Space}+",
+        )
     })
 }
 
 #[inline]
 fn parse_comment(state: Input) -> Output {
     state.rule(BootstrapRule::Comment, |s| {
-        s.match_regex({
-            static REGEX: OnceLock<Regex> = OnceLock::new();
-            REGEX.get_or_init(|| Regex::new("^(\\/\\/[^\\n\\r]*)").unwrap())
-        })
+        s.match_regex(
+            {
+                static REGEX: OnceLock<Regex> = OnceLock::new();
+                REGEX.get_or_init(|| Regex::new("^(\\/\\/[^\\n\\r]*)").unwrap())
+            },
+            "\\/\\/[^\\n\\r]*",
+        )
     })
 }
 
@@ -905,6 +1219,6 @@ fn builtin_text<'i>(state: Input<'i>, text: &'static str, case: bool) -> Output<
     state.rule(BootstrapRule::IgnoreText, |s| s.match_string(text, case))
 }
 
-fn builtin_regex<'i, 'r>(state: Input<'i>, regex: &'r Regex) -> Output<'i> {
-    state.rule(BootstrapRule::IgnoreRegex, |s| s.match_regex(regex))
+fn builtin_regex<'i, 'r>(state: Input<'i>, regex: &'r Regex, raw: &str) -> Output<'i> {
+    state.rule(BootstrapRule::IgnoreRegex, |s| s.match_regex(regex, raw))
 }
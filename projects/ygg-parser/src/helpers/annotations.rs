@@ -1,4 +1,6 @@
-use crate::bootstrap::{CallBodyNode, ClassStatementNode, GroupPairNode, GroupStatementNode, UnionStatementNode};
+use crate::bootstrap::{
+    CallBodyNode, ClassStatementNode, ClimbStatementNode, ExpressionNode, GroupPairNode, GroupStatementNode, UnionStatementNode,
+};
 
 use super::*;
 
@@ -16,6 +18,12 @@ impl UnionStatementNode {
     }
 }
 
+impl ClimbStatementNode {
+    pub fn annotations(&self) -> TakeAnnotations {
+        TakeAnnotations { auto_tag: self.op_remark.is_none(), macros: &self.decorator_call, modifiers: &self.modifier_call }
+    }
+}
+
 impl GroupStatementNode {
     pub fn annotations(&self) -> TakeAnnotations {
         TakeAnnotations { auto_tag: false, macros: &self.decorator_call, modifiers: &self.modifier_call }
@@ -42,6 +50,26 @@ impl<'i> TakeAnnotations<'i> {
     pub fn get_entry(&self) -> Option<bool> {
         self.find_modifiers(&["entry"], &[])
     }
+    /// `extend union` appends branches to the union of the base grammar
+    pub fn get_extend(&self) -> Option<bool> {
+        self.find_modifiers(&["extend"], &[])
+    }
+
+    /// `memo class` or `#memo()`
+    pub fn get_memo(&self) -> Option<bool> {
+        match self.find_functions("memo").next() {
+            Some(_) => Some(true),
+            None => self.find_modifiers(&["memo"], &[]),
+        }
+    }
+
+    /// `inline class` or `#inline()`
+    pub fn get_inline(&self) -> Option<bool> {
+        match self.find_functions("inline").next() {
+            Some(_) => Some(true),
+            None => self.find_modifiers(&["inline"], &[]),
+        }
+    }
 
     pub fn get_keep(&self) -> Option<bool> {
         self.find_modifiers(&["keep"], &[])
@@ -55,6 +83,19 @@ impl<'i> TakeAnnotations<'i> {
     pub fn get_text_capture(&self) -> Option<bool> {
         self.find_modifiers(&["text"], &[])
     }
+    /// Arguments of `#recover(';', Statement)`
+    pub fn get_recover(&self) -> Vec<&ExpressionNode> {
+        self.find_functions("recover").flat_map(|body| body.expression.iter()).collect()
+    }
+    /// Argument of `@label("expression")`
+    pub fn get_label(&self) -> Option<&ExpressionNode> {
+        self.find_functions("label").flat_map(|body| body.expression.first()).next()
+    }
+    /// Argument of `@error("expected a statement")`
+    pub fn get_error(&self) -> Option<&ExpressionNode> {
+        self.find_functions("error").flat_map(|body| body.expression.first()).next()
+    }
+    /// Arguments of `@style(keyword)`
     pub fn get_styles(&self) -> Vec<String> {
         let mut out = vec![];
         for body in self.find_functions("style") {
@@ -34,6 +34,41 @@ mod standard {
         println!("{ast:#?}")
     }
 
+    #[test]
+    fn test_macro() {
+        let text = r##"macro sep_by(item, sep) {
+    (item (sep item)*)?
+}"##;
+        let ast = MacroStatementNode::from_str(text).unwrap();
+        let names: Vec<_> = ast.macro_parameters.unwrap().identifier.into_iter().map(|v| v.text).collect();
+        assert_eq!(ast.name.text, "sep_by");
+        assert_eq!(names, ["item", "sep"]);
+        let ast = RootNode::from_str("def Word { @sep_by(Letter, '-') }").unwrap();
+        assert!(matches!(ast.statement.as_slice(), [StatementNode::MacroStatement(v)] if v.macro_parameters.is_none()));
+    }
+
+    #[test]
+    fn test_import() {
+        let text = r##"using "common.ygg" { Identifier, String as Str, }"##;
+        let ast = ImportStatementNode::from_str(text).unwrap();
+        let items: Vec<_> =
+            ast.import_block.unwrap().import_item.into_iter().map(|v| (v.name.text, v.alias.map(|v| v.text))).collect();
+        assert!(matches!(ast.path, StringNode::Normal(_)));
+        assert_eq!(items, [("Identifier".to_string(), None), ("String".to_string(), Some("Str".to_string()))]);
+        let ast = RootNode::from_str("import 'common.ygg'\nclass A { Identifier }").unwrap();
+        assert!(matches!(ast.statement.as_slice(), [StatementNode::ImportStatement(v), StatementNode::ClassStatement(_)] if v.import_block.is_none()));
+    }
+
+    #[test]
+    fn test_inherit() {
+        let ast = GrammarStatementNode::from_str("grammar Json5 : Json { -Comment -Null }").unwrap();
+        let removed: Vec<_> = ast.grammar_block.identifier.into_iter().map(|v| v.text).collect();
+        assert_eq!(ast.base.map(|v| v.text), Some("Json".to_string()));
+        assert_eq!(removed, ["Comment", "Null"]);
+        let ast = GrammarStatementNode::from_str("grammar Json {}").unwrap();
+        assert!(ast.base.is_none() && ast.grammar_block.identifier.is_empty());
+    }
+
     #[test]
     fn test_regex() {
         let text = r##"/([^\\\\\\/]|\\\\.)+/"##;
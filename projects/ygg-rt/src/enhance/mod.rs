@@ -1,33 +1,53 @@
 use regex_automata::dfa::{dense::DFA, regex::Regex};
 
 pub mod stack;
+mod trie;
+
+pub use self::trie::TextTrie;
 
 /// A precompiled regular expression state machine
+///
+/// The dense DFAs are serialized by the compiler in both endiannesses, only the native one is used.
 pub struct RegexCompiled {
-    /// FWD
+    /// The regex in the grammar, shown in errors
+    pub raw: &'static str,
+    /// The forward DFA in little endian
     pub forward_le: &'static [u8],
-    /// FWD
+    /// The reverse DFA in little endian
     pub reverse_le: &'static [u8],
-    /// FWD
+    /// The forward DFA in big endian
     pub forward_be: &'static [u8],
-    /// FWD
+    /// The reverse DFA in big endian
     pub reverse_be: &'static [u8],
 }
 
+/// Aligns the serialized DFA to `u32`, a plain byte array has no alignment
+///
+/// ```
+/// # use yggdrasil_rt::RegexAligned;
+/// const BYTES: &[u8] = &RegexAligned { align: [], bytes: [0u8; 4] }.bytes;
+/// assert_eq!(BYTES.as_ptr() as usize % 4, 0);
+/// ```
+#[repr(C)]
+pub struct RegexAligned<B: ?Sized> {
+    /// Zero sized, only used for the alignment
+    pub align: [u32; 0],
+    /// The serialized DFA
+    pub bytes: B,
+}
+
 impl RegexCompiled {
-    /// FWD
+    /// Deserialize the DFAs of the native endianness, this doesn't copy or compile anything
     pub fn regex(&self) -> Regex<DFA<&[u32]>> {
+        let (forward, reverse) = match cfg!(target_endian = "little") {
+            true => (self.forward_le, self.reverse_le),
+            false => (self.forward_be, self.reverse_be),
+        };
+        // SAFETY: the bytes are serialized by `DFA::to_bytes_*_endian`
         unsafe {
-            if cfg!(target_endian = "little") {
-                let fwd: DFA<&[u32]> = DFA::from_bytes_unchecked(self.forward_le).unwrap().0;
-                let rev: DFA<&[u32]> = DFA::from_bytes_unchecked(self.reverse_le).unwrap().0;
-                Regex::builder().build_from_dfas(fwd, rev)
-            }
-            else {
-                let fwd: DFA<&[u32]> = DFA::from_bytes_unchecked(self.forward_be).unwrap().0;
-                let rev: DFA<&[u32]> = DFA::from_bytes_unchecked(self.forward_be).unwrap().0;
-                Regex::builder().build_from_dfas(fwd, rev)
-            }
+            let fwd: DFA<&[u32]> = DFA::from_bytes_unchecked(forward).unwrap().0;
+            let rev: DFA<&[u32]> = DFA::from_bytes_unchecked(reverse).unwrap().0;
+            Regex::builder().build_from_dfas(fwd, rev)
         }
     }
 }
@@ -0,0 +1,80 @@
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+/// A set of literals matched in one pass, used for choices made only of texts.
+///
+/// Like an ordered choice, the first literal in the set that matches wins, not the longest one.
+#[derive(Clone, Debug)]
+pub struct TextTrie {
+    nodes: Vec<TrieNode>,
+    insensitive: bool,
+    /// The literals, shown in errors
+    texts: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TrieNode {
+    next: BTreeMap<u8, usize>,
+    /// Index of the literal ends here
+    accept: Option<usize>,
+}
+
+impl TextTrie {
+    /// Build the trie from the literals in the order of the choice
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::TextTrie;
+    /// let trie = TextTrie::new(&["in", "for", "foreach"], false);
+    /// assert_eq!(trie.find("foreach"), Some(3));
+    /// assert_eq!(trie.find("int"), Some(2));
+    /// assert_eq!(trie.find("fo"), None);
+    /// ```
+    pub fn new(texts: &[&str], insensitive: bool) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+        for (index, text) in texts.iter().enumerate() {
+            let mut node = 0;
+            for byte in text.bytes() {
+                let byte = if insensitive { byte.to_ascii_lowercase() } else { byte };
+                node = match nodes[node].next.get(&byte) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let next = nodes.len() - 1;
+                        nodes[node].next.insert(byte, next);
+                        next
+                    }
+                };
+            }
+            nodes[node].accept.get_or_insert(index);
+        }
+        Self { nodes, insensitive, texts: texts.iter().map(|s| s.to_string()).collect() }
+    }
+    /// The literals in the order of the choice
+    pub fn texts(&self) -> &[String] {
+        &self.texts
+    }
+    /// Returns the length of the first literal that the `input` starts with
+    pub fn find(&self, input: &str) -> Option<usize> {
+        let mut node = 0;
+        let mut best = self.nodes[0].accept.map(|index| (index, 0));
+        for (offset, byte) in input.bytes().enumerate() {
+            let byte = if self.insensitive { byte.to_ascii_lowercase() } else { byte };
+            node = match self.nodes[node].next.get(&byte) {
+                Some(next) => *next,
+                None => break,
+            };
+            if let Some(index) = self.nodes[node].accept {
+                if best.is_none_or(|(best, _)| index < best) {
+                    best = Some((index, offset + 1))
+                }
+            }
+        }
+        best.map(|(_, length)| length)
+    }
+}
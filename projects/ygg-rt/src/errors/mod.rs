@@ -7,6 +7,9 @@ use alloc::{
 };
 use core::{cmp, fmt, mem};
 
+#[cfg(feature = "std")]
+pub(crate) mod render;
+
 /// Parse-related error type.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct YggdrasilError<R> {
@@ -25,11 +28,18 @@ pub struct YggdrasilError<R> {
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum ErrorKind<R> {
     /// Generated parsing error with expected and unexpected `Rule`s
+    ///
+    /// More fields may be added, use [`ErrorKind::parsing_error`] to create one and `..` to match one.
+    #[non_exhaustive]
     ParsingError {
         /// Positive attempts
         positives: Vec<R>,
         /// Negative attempts
         negatives: Vec<R>,
+        /// Texts and regexes attempted, such as `` `;` `` or `/[0-9]+/`, and the labels of the expressions
+        literals: Vec<String>,
+        /// The character at the error, `None` at the end of input
+        found: Option<char>,
     },
     /// Unable to convert given node to ast
     InvalidNode {
@@ -94,10 +104,7 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// # let input = "";
     /// # let pos = Position::from_start(input);
     /// let error = YggdrasilError::new_from_offset(
-    ///     ErrorKind::ParsingError {
-    ///         positives: vec![Rule::open_paren],
-    ///         negatives: vec![Rule::closed_paren],
-    ///     },
+    ///     ErrorKind::parsing_error(vec![Rule::open_paren], vec![Rule::closed_paren]),
     ///     pos,
     /// );
     ///
@@ -136,10 +143,7 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// # let end = start.clone();
     /// # let span = start.span(&end);
     /// let error = YggdrasilError::new_from_span(
-    ///     ErrorKind::ParsingError {
-    ///         positives: vec![Rule::open_paren],
-    ///         negatives: vec![Rule::closed_paren],
-    ///     },
+    ///     ErrorKind::parsing_error(vec![Rule::open_paren], vec![Rule::closed_paren]),
     ///     span,
     /// );
     ///
@@ -203,10 +207,7 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// # let input = "";
     /// # let pos = Position::from_start(input);
     /// YggdrasilError::new_from_offset(
-    ///     ErrorKind::ParsingError {
-    ///         positives: vec![Rule::open_paren],
-    ///         negatives: vec![Rule::closed_paren],
-    ///     },
+    ///     ErrorKind::parsing_error(vec![Rule::open_paren], vec![Rule::closed_paren]),
     ///     pos,
     /// )
     /// .with_path("file.rs");
@@ -234,10 +235,7 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// # let input = "";
     /// # let pos = Position::from_start(input);
     /// # let error = YggdrasilError::new_from_offset(
-    /// #     ErrorKind::ParsingError {
-    /// #         positives: vec![Rule::open_paren],
-    /// #         negatives: vec![Rule::closed_paren]
-    /// #     },
+    /// #     ErrorKind::parsing_error(vec![Rule::open_paren], vec![Rule::closed_paren]),
     /// #     pos);
     /// let error = error.with_path("file.rs");
     /// assert_eq!(Some("file.rs"), error.path());
@@ -254,7 +252,10 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// Renames all `Rule`s if this is a [`ParsingError`]. It does nothing when called on a
     /// [`CustomError`].
     ///
-    /// Useful in order to rename verbose rules or have detailed per-`Rule` formatting.
+    /// Useful in order to rename verbose rules or have detailed per-`Rule` formatting. The labels given by
+    /// [`State::label`] are kept as they are.
+    ///
+    /// [`State::label`]: crate::State::label
     ///
     /// [`ParsingError`]: enum.ErrorVariant.html#variant.ParsingError
     /// [`CustomError`]: enum.ErrorVariant.html#variant.CustomError
@@ -274,10 +275,7 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
     /// # let input = "";
     /// # let pos = Position::from_start(input);
     /// YggdrasilError::new_from_offset(
-    ///     ErrorKind::ParsingError {
-    ///         positives: vec![Rule::open_paren],
-    ///         negatives: vec![Rule::closed_paren],
-    ///     },
+    ///     ErrorKind::parsing_error(vec![Rule::open_paren], vec![Rule::closed_paren]),
     ///     pos,
     /// )
     /// .renamed_rules(|rule| match *rule {
@@ -290,8 +288,8 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
         F: FnMut(&R) -> String,
     {
         let variant = match self.variant {
-            ErrorKind::ParsingError { positives, negatives } => {
-                let message = YggdrasilError::parsing_error_message(&positives, &negatives, f);
+            ErrorKind::ParsingError { positives, negatives, literals, found } => {
+                let message = YggdrasilError::parsing_error_message(&positives, &negatives, &literals, found, f);
                 ErrorKind::CustomError { message }
             }
             variant => variant,
@@ -372,36 +370,24 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
         self.variant.message().to_string()
     }
 
-    fn parsing_error_message<F>(positives: &[R], negatives: &[R], mut f: F) -> String
+    fn parsing_error_message<F>(positives: &[R], negatives: &[R], literals: &[String], found: Option<char>, mut f: F) -> String
     where
         F: FnMut(&R) -> String,
     {
-        match (negatives.is_empty(), positives.is_empty()) {
+        let mut expected = literals.to_vec();
+        expected.extend(positives.iter().map(&mut f));
+        let unexpected = negatives.iter().map(&mut f).collect::<Vec<_>>();
+        let found = match found {
+            Some(c) => format!("`{}`", c.escape_debug()),
+            None => "end of input".to_owned(),
+        };
+        match (unexpected.is_empty(), expected.is_empty()) {
             (false, false) => {
-                format!(
-                    "unexpected {}; expected {}",
-                    YggdrasilError::enumerate(negatives, &mut f),
-                    YggdrasilError::enumerate(positives, &mut f)
-                )
-            }
-            (false, true) => format!("unexpected {}", YggdrasilError::enumerate(negatives, &mut f)),
-            (true, false) => format!("expected {}", YggdrasilError::enumerate(positives, &mut f)),
-            (true, true) => "unknown parsing error".to_owned(),
-        }
-    }
-
-    fn enumerate<F>(rules: &[R], f: &mut F) -> String
-    where
-        F: FnMut(&R) -> String,
-    {
-        match rules.len() {
-            1 => f(&rules[0]),
-            2 => format!("{} or {}", f(&rules[0]), f(&rules[1])),
-            l => {
-                let non_separated = f(&rules[l - 1]);
-                let separated = rules.iter().take(l - 1).map(f).collect::<Vec<_>>().join(", ");
-                format!("{}, or {}", separated, non_separated)
+                format!("unexpected {}; expected {}, found {}", enumerate(&unexpected), enumerate(&expected), found)
             }
+            (false, true) => format!("unexpected {}", enumerate(&unexpected)),
+            (true, false) => format!("expected {}, found {}", enumerate(&expected), found),
+            (true, true) => format!("unexpected {}", found),
         }
     }
 
@@ -477,6 +463,10 @@ impl<R: YggdrasilRule> YggdrasilError<R> {
 }
 
 impl<R: YggdrasilRule> ErrorKind<R> {
+    /// A [`ParsingError`](ErrorKind::ParsingError) with the expected and unexpected rules, but no literals
+    pub fn parsing_error(positives: Vec<R>, negatives: Vec<R>) -> Self {
+        ErrorKind::ParsingError { positives, negatives, literals: Vec::new(), found: None }
+    }
     /// Returns the error message for [`ErrorVariant`]
     ///
     /// If [`ErrorVariant`] is [`CustomError`], it returns a
@@ -500,8 +490,8 @@ impl<R: YggdrasilRule> ErrorKind<R> {
     /// println!("{}", variant.message());
     pub fn message(&self) -> Cow<'_, str> {
         match self {
-            ErrorKind::ParsingError { ref positives, ref negatives } => {
-                Cow::Owned(YggdrasilError::parsing_error_message(positives, negatives, |r| format!("{:?}", r)))
+            ErrorKind::ParsingError { ref positives, ref negatives, ref literals, found } => {
+                Cow::Owned(YggdrasilError::parsing_error_message(positives, negatives, literals, *found, rule_words))
             }
             ErrorKind::CustomError { ref message } => Cow::Borrowed(message),
             ErrorKind::InvalidNode { expect } => Cow::Owned(format!("invalid node, expected node {expect:?}")),
@@ -536,3 +526,30 @@ impl<R: YggdrasilRule> fmt::Display for ErrorKind<R> {
 fn visualize_whitespace(input: &str) -> String {
     input.to_owned().replace('\r', "␍").replace('\n', "␊")
 }
+
+/// `a`, `b` or `c`
+fn enumerate(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.to_owned(),
+        [rest @ .., last] => format!("{} or {}", rest.join(", "), last),
+    }
+}
+
+/// `StringLiteral` and `string_literal` are both shown as `string literal`
+fn rule_words<R: YggdrasilRule>(rule: &R) -> String {
+    let mut out = String::new();
+    for c in format!("{:?}", rule).chars() {
+        match c {
+            '_' => out.push(' '),
+            c if c.is_uppercase() => {
+                if !out.is_empty() && !out.ends_with(' ') {
+                    out.push(' ')
+                }
+                out.extend(c.to_lowercase())
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
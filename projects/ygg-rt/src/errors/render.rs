@@ -0,0 +1,191 @@
+use super::{enumerate, rule_words, ErrorKind, InputLocation, YggdrasilError};
+use crate::YggdrasilRule;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write as _;
+use std::io::{self, IsTerminal, Write};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders errors with the source snippet they point to, in the style of `rustc`
+///
+/// ```text
+/// error: expected `;`, found `1`
+///  --> main.ygg:1:3
+///   |
+/// 1 | a 1
+///   |   ^ expected `;`
+/// ```
+///
+/// Spans over several lines are drawn with a bar on the left, the lines in the middle of long spans are elided.
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{state, ErrorRenderer, YggdrasilRule};
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// # enum Rule {
+/// #     greeting,
+/// # }
+/// # impl YggdrasilRule for Rule {}
+/// let input = "bye";
+/// let error = state(input, |s| s.rule(Rule::greeting, |s| s.match_string("hi", false))).unwrap_err();
+/// let text = ErrorRenderer::new(input).with_color(false).render(&error.with_path("a.txt"));
+/// assert_eq!(text, "error: expected greeting, found `b`\n --> a.txt:1:1\n  |\n1 | bye\n  | ^ expected greeting\n");
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ErrorRenderer<'i> {
+    source: &'i str,
+    color: bool,
+}
+
+impl<'i> ErrorRenderer<'i> {
+    /// Create a renderer for errors in `source`, colored when the standard error is a terminal and `NO_COLOR` is not set
+    pub fn new(source: &'i str) -> Self {
+        let color = io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        Self { source, color }
+    }
+    /// Enable or disable the ANSI colors
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+    /// Render the error as text, ends with a line break
+    pub fn render<R: YggdrasilRule>(&self, error: &YggdrasilError<R>) -> String {
+        let mut w = String::new();
+        let (start, end) = match error.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span((start, end)) => (start.min(end), start.max(end)),
+        };
+        let lines = Snippet::new(self.source);
+        let (start_line, start_col) = lines.locate(start);
+        // the last character of the span, not the one after it
+        let (end_line, end_col) = match end > start {
+            true => lines.locate(end - 1),
+            false => (start_line, start_col),
+        };
+        let width = (end_line + 1).to_string().len();
+        let gutter = " ".repeat(width);
+        let label = error.variant.label();
+        // header
+        writeln!(w, "{}: {}", self.paint(RED, "error"), self.paint(BOLD, &error.variant.message())).unwrap();
+        let path = error.path().map(|path| format!("{}:", path)).unwrap_or_default();
+        writeln!(w, "{}{} {}{}:{}", gutter, self.paint(BLUE, "-->"), path, start_line + 1, start_col + 1).unwrap();
+        writeln!(w, "{} {}", gutter, self.paint(BLUE, "|")).unwrap();
+        // snippet
+        if start_line == end_line {
+            let text = lines.text(start_line);
+            let marks = "^".repeat(end_col - start_col + 1);
+            self.source_line(&mut w, width, start_line, "", text);
+            let padding = indent(text, start_col);
+            self.mark_line(&mut w, &gutter, "", &format!("{padding}{marks}"), &label);
+        }
+        else {
+            self.source_line(&mut w, width, start_line, "  ", lines.text(start_line));
+            self.mark_line(&mut w, &gutter, " ", &format!("{}^", "_".repeat(start_col + 1)), "");
+            let middle = start_line + 1..end_line;
+            for line in middle.clone() {
+                // long spans show the first and the last lines only
+                if middle.len() > 3 && line > start_line + 1 && line < end_line - 1 {
+                    if line == start_line + 2 {
+                        writeln!(w, "{} {}", self.paint(BLUE, &".".repeat(width)), self.paint(RED, "|")).unwrap();
+                    }
+                    continue;
+                }
+                let bar = format!("{} ", self.paint(RED, "|"));
+                self.source_line(&mut w, width, line, &bar, lines.text(line));
+            }
+            let bar = format!("{} ", self.paint(RED, "|"));
+            self.source_line(&mut w, width, end_line, &bar, lines.text(end_line));
+            self.mark_line(&mut w, &gutter, "|", &format!("{}^", "_".repeat(end_col + 1)), &label);
+        }
+        w
+    }
+    /// Write the rendered error to `w`
+    pub fn write<R: YggdrasilRule, W: Write>(&self, error: &YggdrasilError<R>, mut w: W) -> io::Result<()> {
+        w.write_all(self.render(error).as_bytes())
+    }
+    /// Print the rendered error to the standard error
+    pub fn eprint<R: YggdrasilRule>(&self, error: &YggdrasilError<R>) {
+        std::eprint!("{}", self.render(error))
+    }
+    fn paint(&self, style: &str, text: &str) -> String {
+        match self.color {
+            true => format!("{style}{text}{RESET}"),
+            false => text.to_string(),
+        }
+    }
+    fn source_line(&self, w: &mut String, width: usize, line: usize, margin: &str, text: &str) {
+        let number = format!("{:width$}", line + 1);
+        writeln!(w, "{} {} {}{}", self.paint(BLUE, &number), self.paint(BLUE, "|"), margin, text).unwrap();
+    }
+    fn mark_line(&self, w: &mut String, gutter: &str, margin: &str, marks: &str, label: &str) {
+        let mut line = format!("{} {} {}", gutter, self.paint(BLUE, "|"), self.paint(RED, &format!("{margin}{marks}")));
+        if !label.is_empty() {
+            line.push(' ');
+            line.push_str(&self.paint(RED, label));
+        }
+        writeln!(w, "{}", line).unwrap();
+    }
+}
+
+impl<R: YggdrasilRule> ErrorKind<R> {
+    /// The label under the marks, the header shows the whole message
+    fn label(&self) -> String {
+        match self {
+            ErrorKind::ParsingError { positives, negatives, literals, .. } => {
+                let mut expected = literals.to_vec();
+                expected.extend(positives.iter().map(rule_words));
+                match expected.is_empty() {
+                    true => format!("unexpected {}", enumerate(&negatives.iter().map(rule_words).collect::<Vec<_>>())),
+                    false => format!("expected {}", enumerate(&expected)),
+                }
+            }
+            ErrorKind::InvalidNode { expect } => format!("expected {}", rule_words(expect)),
+            ErrorKind::InvalidTag { expect } => format!("expected tag `{}`", expect),
+            ErrorKind::CustomError { .. } => String::new(),
+        }
+    }
+}
+
+/// The lines of the source and their offsets
+struct Snippet<'i> {
+    source: &'i str,
+    starts: Vec<usize>,
+}
+
+impl<'i> Snippet<'i> {
+    fn new(source: &'i str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+        Self { source, starts }
+    }
+    /// Line and column of the offset, both counted from 0, the column counts characters
+    fn locate(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.starts.partition_point(|start| *start <= offset) - 1;
+        let column = self.source[self.starts[line]..offset].chars().count();
+        (line, column)
+    }
+    /// The text of the line without the line break
+    fn text(&self, line: usize) -> &'i str {
+        let end = self.starts.get(line + 1).copied().unwrap_or(self.source.len());
+        self.source[self.starts[line]..end].trim_end_matches(['\n', '\r'])
+    }
+}
+
+/// Spaces up to the column, tabs are kept so the marks line up
+fn indent(text: &str, column: usize) -> String {
+    text.chars().chain(core::iter::repeat(' ')).take(column).map(|c| if c == '\t' { '\t' } else { ' ' }).collect()
+}
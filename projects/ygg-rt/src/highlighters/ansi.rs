@@ -0,0 +1,65 @@
+use super::StyledText;
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+};
+
+/// Renders [`StyledText`] with the ANSI escape codes of a terminal
+///
+/// The first name of a style found in the theme is used, text without a known style is left as it is.
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{AnsiHighlighter, StyledText};
+/// let highlighter = AnsiHighlighter::default().with_style("keyword", "\x1b[31m");
+/// assert_eq!(highlighter.render(&StyledText::default()), "");
+/// ```
+#[derive(Clone, Debug)]
+pub struct AnsiHighlighter {
+    theme: BTreeMap<String, String>,
+}
+
+impl Default for AnsiHighlighter {
+    /// The styles used by the grammars of yggdrasil
+    fn default() -> Self {
+        let theme = [
+            ("keyword", "\x1b[35m"),
+            ("operator", "\x1b[35m"),
+            ("comment", "\x1b[2;3m"),
+            ("string", "\x1b[32m"),
+            ("text", "\x1b[32m"),
+            ("escape", "\x1b[33m"),
+            ("number", "\x1b[36m"),
+            ("constant", "\x1b[36m"),
+            ("function", "\x1b[34m"),
+            ("field", "\x1b[31m"),
+            ("type", "\x1b[33m"),
+            ("annotation", "\x1b[33m"),
+        ];
+        Self { theme: theme.into_iter().map(|(name, code)| (name.to_string(), code.to_string())).collect() }
+    }
+}
+
+impl AnsiHighlighter {
+    /// Set the escape code of the style, such as `"\x1b[1;31m"` for bold red
+    pub fn with_style(mut self, style: &str, code: &str) -> Self {
+        self.theme.insert(style.to_string(), code.to_string());
+        self
+    }
+    /// Render the text, each styled piece is reset at its end
+    pub fn render(&self, text: &StyledText) -> String {
+        let mut out = String::new();
+        for span in text.spans() {
+            match span.style.split_whitespace().find_map(|name| self.theme.get(name)) {
+                Some(code) => {
+                    out.push_str(code);
+                    out.push_str(span.text);
+                    out.push_str("\x1b[0m");
+                }
+                None => out.push_str(span.text),
+            }
+        }
+        out
+    }
+}
@@ -0,0 +1,67 @@
+use super::StyledText;
+use alloc::string::{String, ToString};
+
+/// Renders [`StyledText`] as HTML, each styled piece is a `<span>` with a class for each name of the style
+///
+/// The result is meant to be put into a `<pre>`, the colors are left to the style sheet.
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{HtmlHighlighter, StyledText};
+/// let highlighter = HtmlHighlighter::default().with_prefix("hl-");
+/// assert_eq!(highlighter.render(&StyledText::default()), "");
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlHighlighter {
+    prefix: String,
+}
+
+impl Default for HtmlHighlighter {
+    fn default() -> Self {
+        Self { prefix: "ygg-".to_string() }
+    }
+}
+
+impl HtmlHighlighter {
+    /// Set the prefix of the class names, `keyword` is written as `ygg-keyword` by default
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+    /// Render the text, the special characters are escaped
+    pub fn render(&self, text: &StyledText) -> String {
+        let mut out = String::new();
+        for span in text.spans() {
+            if span.style.is_empty() {
+                escape(&mut out, span.text);
+                continue;
+            }
+            out.push_str("<span class=\"");
+            for (i, name) in span.style.split_whitespace().enumerate() {
+                if i != 0 {
+                    out.push(' ');
+                }
+                out.push_str(&self.prefix);
+                escape(&mut out, name);
+            }
+            out.push_str("\">");
+            escape(&mut out, span.text);
+            out.push_str("</span>");
+        }
+        out
+    }
+}
+
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+}
@@ -1 +1,103 @@
+use crate::{Token, TokenTree, Tokens, YggdrasilRule};
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
 
+pub use self::{ansi::AnsiHighlighter, html::HtmlHighlighter};
+
+mod ansi;
+mod html;
+
+/// The input split into pieces by the styles of the rules, see [`YggdrasilRule::get_style`]
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{state, StyledText, YggdrasilRule};
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// # enum Rule {
+/// #     statement,
+/// #     keyword,
+/// # }
+/// impl YggdrasilRule for Rule {
+///     fn get_style(&self) -> &'static str {
+///         match self {
+///             Self::keyword => "keyword",
+///             _ => "",
+///         }
+///     }
+/// }
+///
+/// let input = "let x";
+/// let tree = state(input, |s| {
+///     s.rule(Rule::statement, |s| {
+///         s.rule(Rule::keyword, |s| s.match_string("let", false)).and_then(|s| s.skip(2))
+///     })
+/// })
+/// .unwrap();
+/// let text = StyledText::from(tree);
+/// let spans: Vec<_> = text.spans().iter().map(|span| (span.style, span.text)).collect();
+/// assert_eq!(spans, [("keyword", "let"), ("", " x")]);
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StyledText<'i> {
+    spans: Vec<StyledSpan<'i>>,
+}
+
+/// A piece of the input with the style of the innermost rule that has one, the rest of the input has the empty style
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StyledSpan<'i> {
+    /// The style of the rule, may contain several names separated by spaces
+    pub style: &'static str,
+    /// The text of the piece
+    pub text: &'i str,
+    /// The offsets of the piece in the input
+    pub range: Range<usize>,
+}
+
+impl<'i> StyledText<'i> {
+    /// Split the input by the tokens, the tokens must come from the same input
+    pub fn new<R: YggdrasilRule>(input: &'i str, tokens: Tokens<'i, R>) -> Self {
+        let mut out = Self::default();
+        let mut styles = vec![];
+        let mut offset = 0;
+        for token in tokens {
+            let position = match &token {
+                Token::Start { pos, .. } | Token::End { pos, .. } => pos.offset(),
+            };
+            out.push(input, offset..position, styles.iter().rev().find(|s: &&&str| !s.is_empty()).copied().unwrap_or(""));
+            offset = position;
+            match token {
+                Token::Start { rule, .. } => styles.push(rule.get_style()),
+                Token::End { .. } => {
+                    styles.pop();
+                }
+            }
+        }
+        out.push(input, offset..input.len(), "");
+        out
+    }
+    /// The pieces in the order of the input, the adjacent pieces with the same style are merged
+    pub fn spans(&self) -> &[StyledSpan<'i>] {
+        &self.spans
+    }
+    fn push(&mut self, input: &'i str, range: Range<usize>, style: &'static str) {
+        if range.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.style == style && last.range.end == range.start => {
+                last.range.end = range.end;
+                last.text = &input[last.range.clone()];
+            }
+            _ => self.spans.push(StyledSpan { style, text: &input[range.clone()], range }),
+        }
+    }
+}
+
+impl<'i, R: YggdrasilRule> From<TokenTree<'i, R>> for StyledText<'i> {
+    fn from(tree: TokenTree<'i, R>) -> Self {
+        let input = tree.get_input();
+        Self::new(input, tree.tokens())
+    }
+}
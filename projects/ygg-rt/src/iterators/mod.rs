@@ -1,11 +1,10 @@
-use crate::{
-    span::TextSpan, TokenPair, TokenQueue, TokenStream, TokenTree, Tokens, YggdrasilError, YggdrasilNode, YggdrasilRule,
-};
+use crate::{span::TextSpan, TokenPair, TokenQueue, TokenStream, TokenTree, Tokens, YggdrasilError, YggdrasilNode, YggdrasilRule};
 use alloc::{
     borrow::{Cow, ToOwned},
     format,
     rc::Rc,
     string::String,
+    vec,
     vec::Vec,
 };
 use core::{
@@ -14,6 +13,7 @@ use core::{
     fmt::{Debug, Display, Formatter},
     hash::{Hash, Hasher},
     iter::Filter,
+    ops::Range,
     ptr,
 };
 
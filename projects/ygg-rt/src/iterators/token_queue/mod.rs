@@ -4,7 +4,7 @@
 ///     increased speed when pushing to the queue
 ///   * it finds its pair in O(1) time instead of O(N), since pair positions are known at parse time
 ///     and can easily be stored instead of recomputed
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum TokenQueue<R> {
     /// Start
     Start {
@@ -29,3 +29,34 @@ pub enum TokenQueue<R> {
         tag: Option<&'static str>,
     },
 }
+
+impl<R: Clone> TokenQueue<R> {
+    /// Copy the token which was queued at `from` to the index `to`, all links are moved together.
+    pub(crate) fn relocate(&self, from: usize, to: usize) -> Self {
+        let mut token = self.clone();
+        match &mut token {
+            TokenQueue::Start { end_token_index, .. } => *end_token_index = *end_token_index - from + to,
+            TokenQueue::End { start_token_index, .. } => *start_token_index = *start_token_index - from + to,
+        }
+        token
+    }
+    /// Copy the token with the links and the input offset mapped, used when the queue is edited.
+    pub(crate) fn shift<I, O>(&self, index: I, offset: O) -> Self
+    where
+        I: Fn(usize) -> usize,
+        O: Fn(usize) -> usize,
+    {
+        let mut token = self.clone();
+        match &mut token {
+            TokenQueue::Start { end_token_index, input_offset } => {
+                *end_token_index = index(*end_token_index);
+                *input_offset = offset(*input_offset);
+            }
+            TokenQueue::End { start_token_index, input_offset, .. } => {
+                *start_token_index = index(*start_token_index);
+                *input_offset = offset(*input_offset);
+            }
+        }
+        token
+    }
+}
@@ -280,6 +280,34 @@ impl<'i, R: YggdrasilRule> TokenTree<'i, R> {
             TokenQueue::Start { input_offset: input_pos, .. } | TokenQueue::End { input_offset: input_pos, .. } => input_pos,
         }
     }
+
+    pub(crate) fn queue(&self) -> &[TokenQueue<R>] {
+        &self.queue
+    }
+
+    /// Indices of the start tokens whose node strictly covers `range`, from the innermost to the outermost.
+    pub(crate) fn covering(&self, range: &Range<usize>) -> Vec<usize> {
+        let mut out = vec![];
+        let mut cursor = self.start;
+        let mut end = self.end;
+        while cursor < end {
+            let close = match self.queue[cursor] {
+                TokenQueue::Start { end_token_index, .. } => end_token_index,
+                _ => unreachable!(),
+            };
+            if self.pos(cursor) < range.start && range.end < self.pos(close) {
+                out.push(cursor);
+                // siblings never overlap, go inside
+                cursor += 1;
+                end = close;
+            }
+            else {
+                cursor = close + 1;
+            }
+        }
+        out.reverse();
+        out
+    }
 }
 
 impl<'i, R: YggdrasilRule> ExactSizeIterator for TokenTree<'i, R> {
@@ -1,4 +1,6 @@
 use crate::{errors::YggdrasilError, TokenStream, TokenTree, YggdrasilRule};
+use alloc::vec::Vec;
+use core::ops::Range;
 
 /// A trait with a single method that parses strings.
 pub trait YggdrasilParser {
@@ -10,4 +12,20 @@ pub trait YggdrasilParser {
     }
     /// Parses a `&str` starting from `rule`.
     fn parse_cst(input: &str, rule: Self::Rule) -> Result<TokenTree<Self::Rule>, YggdrasilError<Self::Rule>>;
+    /// Parses a `&str` starting from `rule`, the broken parts are skipped and all errors are collected.
+    ///
+    /// Generated parsers implement it with [`state_recover`](crate::state_recover).
+    fn parse_cst_recover(input: &str, rule: Self::Rule) -> (TokenTree<'_, Self::Rule>, Vec<YggdrasilError<Self::Rule>>);
+    /// Parses the `input` again after the text in `edit` of the `old` input was replaced by `text`, the
+    /// nodes not touched by the edit are reused.
+    ///
+    /// Generated parsers implement it with [`state_reparse`](crate::state_reparse).
+    #[allow(clippy::result_large_err)]
+    fn reparse_cst<'i>(
+        old: &TokenTree<Self::Rule>,
+        edit: Range<usize>,
+        text: &str,
+        input: &'i str,
+        rule: Self::Rule,
+    ) -> Result<TokenTree<'i, Self::Rule>, YggdrasilError<Self::Rule>>;
 }
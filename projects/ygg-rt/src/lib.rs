@@ -5,6 +5,8 @@
 #![doc = include_str!("../readme.md")]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 use core::{fmt::Debug, hash::Hash};
 
@@ -15,8 +17,9 @@ pub use regex_automata::meta::Regex;
 
 pub use crate::{
     ast::YggdrasilNode,
-    enhance::{stack::Stack, RegexCompiled},
+    enhance::{stack::Stack, RegexAligned, RegexCompiled, TextTrie},
     errors::YggdrasilError,
+    highlighters::{AnsiHighlighter, HtmlHighlighter, StyledSpan, StyledText},
     iterators::{
         token_pair::{TokenPair, TokenTreeFilterRule, TokenTreeFilterTag},
         token_queue::TokenQueue,
@@ -25,12 +28,15 @@ pub use crate::{
         tokens::Tokens,
     },
     language::YggdrasilParser,
-    parser_state::{state, Either, Lookahead, MatchDir, State},
+    parser_state::{state, state_recover, state_reparse, Either, Lookahead, MatchDir, State},
     position::Position,
     span::{merge_spans, Lines, LinesSpan, TextSpan},
     token::Token,
 };
 
+#[cfg(feature = "std")]
+pub use crate::errors::render::ErrorRenderer;
+
 mod errors;
 mod iterators;
 mod language;
@@ -54,7 +60,11 @@ pub trait YggdrasilRule: Clone + Debug + Eq + Hash + Ord {
     fn is_ignore(&self) -> bool {
         false
     }
-    /// Get the style name from the rule
+    /// Labels of the texts and regexes, the attempted literal is reported in errors instead of the label
+    fn is_literal(&self) -> bool {
+        false
+    }
+    /// Get the style name from the rule, such as `keyword` or `comment`, used by [`StyledText`]
     fn get_style(&self) -> &'static str {
         ""
     }
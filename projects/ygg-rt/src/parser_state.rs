@@ -5,7 +5,15 @@ use crate::{
     span::TextSpan,
     TokenQueue, TokenTree, YggdrasilRule,
 };
-use alloc::{boxed::Box, rc::Rc, vec, vec::Vec};
+use alloc::{
+    boxed::Box,
+    collections::BTreeMap,
+    format,
+    rc::Rc,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use core::ops::Range;
 
 /// The current lookahead status of a [`ParserState`].
@@ -54,13 +62,37 @@ pub struct State<'i, R>
 where
     R: YggdrasilRule,
 {
+    input: &'i str,
     position: Position<'i>,
     queue: Vec<TokenQueue<R>>,
     lookahead: Lookahead,
     pos_attempts: Vec<R>,
     neg_attempts: Vec<R>,
+    /// Texts and regexes attempted at `attempt_pos`, reported instead of the built-in rules
+    literals: Vec<String>,
+    /// Labels of the expressions attempted at `attempt_pos`, reported instead of their attempts
+    labels: Vec<String>,
+    /// Message of the [`State::error`] that failed at `attempt_pos`, until anything else is attempted there
+    message: Option<String>,
     attempt_pos: usize,
+    /// A [`State::cut`] was passed in the current sequence
+    cut: bool,
+    /// A sequence failed after a [`State::cut`], nothing backtracks until the failure is recovered
+    committed: bool,
     stack: Stack<TextSpan<'i>>,
+    memo: BTreeMap<(R, usize), Option<Memo<'i, R>>>,
+    growing: BTreeMap<(R, usize), Option<Memo<'i, R>>>,
+    recovery: bool,
+    errors: Vec<YggdrasilError<R>>,
+}
+
+/// A successful match recorded by [`State::rule_memo`] or [`State::rule_left`].
+#[derive(Debug)]
+struct Memo<'i, R> {
+    /// Where the match ends
+    position: Position<'i>,
+    /// Tokens produced by the match, indexed from 0
+    tokens: Vec<TokenQueue<R>>,
 }
 
 /// Creates a `ParserState` from a `&str`, supplying it to a closure `f`.
@@ -85,22 +117,152 @@ where
             let len = state.queue.len();
             Ok(TokenTree::new(Rc::new(state.queue), input, 0, len))
         }
+        Err(mut state) => Err(state.parsing_error()),
+    }
+}
+
+/// Creates a `ParserState` in recovery mode from a `&str`, supplying it to a closure `f`.
+///
+/// Rules wrapped by [`State::recover`] skip the broken input instead of failing, so the tree is always
+/// returned, together with all the errors.
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{state_recover, Either, State, YggdrasilRule};
+/// # impl YggdrasilRule for Rule {}
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     digit,
+///     error,
+/// }
+/// fn digit(state: Box<State<Rule>>) -> Either<Box<State<Rule>>> {
+///     state.rule(Rule::digit, |s| s.match_range('0'..'9'))
+/// }
+/// // skip until the next digit
+/// let (tree, errors) = state_recover("1ab2", |s| {
+///     s.repeat(0..9, |s| s.recover(Rule::error, digit, |s| s.lookahead(true, digit)))
+/// });
+/// let rules: Vec<_> = tree.map(|pair| pair.get_rule()).collect();
+/// assert_eq!(rules, [Rule::digit, Rule::error, Rule::digit]);
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn state_recover<'i, R, F>(input: &'i str, f: F) -> (TokenTree<'i, R>, Vec<YggdrasilError<R>>)
+where
+    F: FnOnce(Box<State<'i, R>>) -> Either<Box<State<'i, R>>>,
+    R: YggdrasilRule,
+{
+    let mut state = State::new(input);
+    state.recovery = true;
+    let mut state = match f(state) {
+        Ok(state) => state,
         Err(mut state) => {
-            let variant = {
-                state.pos_attempts.sort();
-                state.pos_attempts.dedup();
-                state.neg_attempts.sort();
-                state.neg_attempts.dedup();
-                ErrorKind::ParsingError { positives: state.pos_attempts.clone(), negatives: state.neg_attempts.clone() }
-            };
+            let error = state.parsing_error();
+            state.errors.push(error);
+            state
+        }
+    };
+    let errors = core::mem::take(&mut state.errors);
+    let len = state.queue.len();
+    (TokenTree::new(Rc::new(state.queue), input, 0, len), errors)
+}
 
-            Err(YggdrasilError::new_from_offset(
-                variant,
-                // TODO(performance): Guarantee state.attempt_pos is a valid position
-                position::Position::new(input, state.attempt_pos).unwrap(),
-            ))
+/// Reparses the `input` after the text in `edit` of the `old` input was replaced by `text`.
+///
+/// The innermost node enclosing the edit is parsed again by `f`, the tokens outside it are reused with
+/// their offsets shifted. If the new node doesn't end where the old one ended, the parent node is tried,
+/// and if no node fits, the whole `input` is parsed from `rule`.
+///
+/// Ignored nodes are never reparsed, and the rules are assumed to be context free.
+///
+/// # Examples
+///
+/// ```
+/// # use yggdrasil_rt::{state, state_reparse, Either, State, YggdrasilRule};
+/// # impl YggdrasilRule for Rule {}
+/// # #[allow(non_camel_case_types)]
+/// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+/// enum Rule {
+///     list,
+///     word,
+/// }
+/// fn parse(state: Box<State<Rule>>, rule: Rule) -> Either<Box<State<Rule>>> {
+///     match rule {
+///         Rule::list => state.rule(Rule::list, |s| {
+///             s.sequence(|s| {
+///                 s.match_string("[", false)
+///                     .and_then(|s| {
+///                         s.repeat(0..9, |s| {
+///                             parse(s, Rule::word)
+///                                 .and_then(|s| s.optional(|s| s.match_string(" ", false)))
+///                         })
+///                     })
+///                     .and_then(|s| s.match_string("]", false))
+///             })
+///         }),
+///         Rule::word => state.rule(Rule::word, |s| s.repeat(1..9, |s| s.match_range('a'..'z'))),
+///     }
+/// }
+/// let old = state("[ab cd]", |s| parse(s, Rule::list)).unwrap();
+/// // `cd` -> `xyz`
+/// let new = state_reparse(&old, 4..6, "xyz", "[ab xyz]", Rule::list, parse).unwrap();
+/// let words: Vec<_> =
+///     new.into_iter().next().unwrap().into_inner().map(|pair| pair.as_str()).collect();
+/// assert_eq!(words, ["ab", "xyz"]);
+/// ```
+#[allow(clippy::result_large_err)]
+pub fn state_reparse<'i, R, F>(
+    old: &TokenTree<R>,
+    edit: Range<usize>,
+    text: &str,
+    input: &'i str,
+    rule: R,
+    f: F,
+) -> Result<TokenTree<'i, R>, YggdrasilError<R>>
+where
+    F: Fn(Box<State<'i, R>>, R) -> Either<Box<State<'i, R>>>,
+    R: YggdrasilRule,
+{
+    let queue = old.queue();
+    let delta = text.len() as isize - edit.len() as isize;
+    for open in old.covering(&edit) {
+        let (close, start) = match queue[open] {
+            TokenQueue::Start { end_token_index, input_offset } => (end_token_index, input_offset),
+            _ => unreachable!(),
+        };
+        let (node, end) = match &queue[close] {
+            TokenQueue::End { rule, input_offset, .. } => (rule.clone(), *input_offset),
+            _ => unreachable!(),
+        };
+        if node.is_ignore() {
+            continue;
         }
+        let mut state = State::new(input);
+        state.position = match Position::new(input, start) {
+            Some(position) => position,
+            None => continue,
+        };
+        // the node must fit into the old hole exactly
+        let tokens = match f(state, node) {
+            Ok(state) if state.position.offset() as isize == end as isize + delta => state.queue,
+            _ => continue,
+        };
+        let grow = tokens.len() as isize - (close + 1 - open) as isize;
+        let index = |i: usize| if i > close { (i as isize + grow) as usize } else { i };
+        let offset = |o: usize| if o >= end { (o as isize + delta) as usize } else { o };
+        let mut out = Vec::with_capacity((queue.len() as isize + grow) as usize);
+        out.extend(queue[..open].iter().map(|t| t.shift(index, offset)));
+        out.extend(tokens.iter().map(|t| t.relocate(0, open)));
+        // the tag was given by the parent node
+        if let (Some(TokenQueue::End { tag: new, .. }), TokenQueue::End { tag, .. }) = (out.last_mut(), &queue[close]) {
+            new.clone_from(tag);
+        }
+        out.extend(queue[close + 1..].iter().map(|t| t.shift(index, offset)));
+        let len = out.len();
+        return Ok(TokenTree::new(Rc::new(out), input, 0, len));
     }
+    state(input, |s| f(s, rule))
 }
 
 impl<'i, R> State<'i, R>
@@ -119,13 +281,23 @@ where
     /// ```
     pub fn new(input: &'i str) -> Box<Self> {
         Box::new(State {
+            input,
             position: Position::from_start(input),
             queue: vec![],
             lookahead: Lookahead::None,
             pos_attempts: vec![],
             neg_attempts: vec![],
+            literals: vec![],
+            labels: vec![],
+            message: None,
             attempt_pos: 0,
+            cut: false,
+            committed: false,
             stack: Stack::new(),
+            memo: BTreeMap::new(),
+            growing: BTreeMap::new(),
+            recovery: false,
+            errors: vec![],
         })
     }
 
@@ -194,13 +366,7 @@ where
         let actual_pos = self.position.offset();
         let index = self.queue.len();
 
-        let (pos_attempts_index, neg_attempts_index) = if actual_pos == self.attempt_pos {
-            (self.pos_attempts.len(), self.neg_attempts.len())
-        }
-        else {
-            // Attempts have not been cleared yet since the attempt_pos is older.
-            (0, 0)
-        };
+        let attempts_index = self.attempts_index(actual_pos);
 
         if self.lookahead == Lookahead::None {
             // Pair's position will only be known after running the closure.
@@ -214,7 +380,7 @@ where
         match result {
             Ok(mut new_state) => {
                 if new_state.lookahead == Lookahead::Negative {
-                    new_state.track(rule.clone(), actual_pos, pos_attempts_index, neg_attempts_index, attempts);
+                    new_state.track(rule.clone(), actual_pos, attempts_index, attempts);
                 }
 
                 if new_state.lookahead == Lookahead::None {
@@ -240,7 +406,7 @@ where
             }
             Err(mut new_state) => {
                 if new_state.lookahead != Lookahead::Negative {
-                    new_state.track(rule, actual_pos, pos_attempts_index, neg_attempts_index, attempts);
+                    new_state.track(rule, actual_pos, attempts_index, attempts);
                 }
 
                 if new_state.lookahead == Lookahead::None {
@@ -252,6 +418,362 @@ where
         }
     }
 
+    /// Same as [`State::rule`], but the result of `rule` at each offset is memorized.
+    ///
+    /// The first attempt runs `f` and records the produced tokens (or the failure), later attempts at
+    /// the same offset replay the record without running `f` again, which avoids exponential
+    /// backtracking. `f` must not depend on the stack, and nothing is recorded inside lookahead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, Either, State, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     a,
+    /// }
+    /// fn a(state: Box<State<Rule>>) -> Either<Box<State<Rule>>> {
+    ///     state.rule_memo(Rule::a, |s| s.match_string("a", false))
+    /// }
+    ///
+    /// let input = "ab";
+    /// // the second `a` is replayed from the memo
+    /// let pairs: Vec<_> = state(input, |s| {
+    ///     s.sequence(|s| a(s).and_then(|s| s.match_string("c", false)))
+    ///         .or_else(|s| s.sequence(|s| a(s).and_then(|s| s.match_string("b", false))))
+    /// })
+    /// .unwrap()
+    /// .collect();
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!(pairs[0].as_str(), "a");
+    /// ```
+    #[inline]
+    pub fn rule_memo<F>(mut self: Box<Self>, rule: R, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+    {
+        if self.lookahead != Lookahead::None {
+            return self.rule(rule, f);
+        }
+        let offset = self.position.offset();
+        let index = self.queue.len();
+        let key = (rule, offset);
+        match self.memo.get(&key) {
+            Some(Some(memo)) => {
+                self.queue.extend(memo.tokens.iter().map(|token| token.relocate(0, index)));
+                self.position = memo.position;
+                Ok(self)
+            }
+            Some(None) => {
+                let attempts = self.attempts_at(offset);
+                let attempts_index = self.attempts_index(offset);
+                self.track(key.0, offset, attempts_index, attempts);
+                Err(self)
+            }
+            None => match self.rule(key.0.clone(), f) {
+                Ok(mut state) => {
+                    let tokens = state.queue[index..].iter().map(|token| token.relocate(index, 0)).collect();
+                    state.memo.insert(key, Some(Memo { position: state.position, tokens }));
+                    Ok(state)
+                }
+                // the final failures are not replayed
+                Err(mut state) => {
+                    if !state.committed {
+                        state.memo.insert(key, None);
+                    }
+                    Err(state)
+                }
+            },
+        }
+    }
+
+    /// Same as [`State::rule`], but `rule` may call itself at the same offset, directly or through other rules.
+    ///
+    /// The seed is grown as described by Warth et al.: the recursive call fails at first, then every round
+    /// replays the previous match to the recursive call, until the match stops getting longer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, Either, State, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     expr,
+    /// }
+    /// // expr = expr '-' [0-9] | [0-9]
+    /// fn expr(state: Box<State<Rule>>) -> Either<Box<State<Rule>>> {
+    ///     state.rule_left(Rule::expr, |s| {
+    ///         s.sequence(|s| {
+    ///             expr(s)
+    ///                 .and_then(|s| s.match_string("-", false))
+    ///                 .and_then(|s| s.match_range('0'..'9'))
+    ///         })
+    ///         .or_else(|s| s.match_range('0'..'9'))
+    ///     })
+    /// }
+    ///
+    /// let pairs = state("1-2-3", expr).unwrap();
+    /// let outer = pairs.into_iter().next().unwrap();
+    /// assert_eq!(outer.as_str(), "1-2-3");
+    /// assert_eq!(outer.into_inner().next().unwrap().as_str(), "1-2");
+    /// ```
+    #[inline]
+    pub fn rule_left<F>(mut self: Box<Self>, rule: R, f: F) -> Either<Box<Self>>
+    where
+        F: Fn(Box<Self>) -> Either<Box<Self>>,
+    {
+        let initial = self.position;
+        let index = self.queue.len();
+        let key = (rule, initial.offset());
+        if let Some(record) = self.growing.get(&key) {
+            return match record {
+                Some(memo) => {
+                    self.queue.extend(memo.tokens.iter().map(|token| token.relocate(0, index)));
+                    self.position = memo.position;
+                    Ok(self)
+                }
+                None => Err(self),
+            };
+        }
+        self.growing.insert(key.clone(), None);
+        loop {
+            let last = self.growing.get(&key).and_then(|record| record.as_ref().map(|memo| memo.position.offset()));
+            let mut state = match self.rule(key.0.clone(), &f) {
+                Ok(s) if last.is_none_or(|last| s.position.offset() > last) => s,
+                // the seed can't be used after a final failure
+                Err(mut s) if s.committed => {
+                    s.growing.remove(&key);
+                    s.queue.truncate(index);
+                    s.position = initial;
+                    return Err(s);
+                }
+                Ok(s) | Err(s) => {
+                    self = s;
+                    break;
+                }
+            };
+            let tokens = state.queue[index..].iter().map(|token| token.relocate(index, 0)).collect();
+            state.growing.insert(key.clone(), Some(Memo { position: state.position, tokens }));
+            // start over with the longer seed
+            state.queue.truncate(index);
+            state.position = initial;
+            self = state;
+        }
+        self.queue.truncate(index);
+        self.position = initial;
+        match self.growing.remove(&key).flatten() {
+            Some(memo) => {
+                self.queue.extend(memo.tokens.into_iter().map(|token| token.relocate(0, index)));
+                self.position = memo.position;
+                Ok(self)
+            }
+            None => Err(self),
+        }
+    }
+
+    /// Match `f`, when it fails in recovery mode, skip the input until `sync` matches, and wrap all the skipped
+    /// text (including what `sync` matched) into a `rule` node.
+    ///
+    /// At least one character is skipped, the error is collected and parsing goes on. Use a positive lookahead
+    /// in `sync` to stop before the recovery point instead, such as the start of the next statement. Outside
+    /// the recovery mode, this is the same as `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state_recover, State, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     statement,
+    ///     error,
+    /// }
+    /// let (tree, errors) = state_recover("a;b?;c;", |s| {
+    ///     s.repeat(0..9, |s| {
+    ///         s.recover(
+    ///             Rule::error,
+    ///             |s| {
+    ///                 s.rule(Rule::statement, |s| {
+    ///                     s.match_range('a'..'z').and_then(|s| s.match_string(";", false))
+    ///                 })
+    ///             },
+    ///             |s| s.match_string(";", false),
+    ///         )
+    ///     })
+    /// });
+    /// let nodes: Vec<_> = tree.map(|pair| (pair.get_rule(), pair.as_str())).collect();
+    /// assert_eq!(nodes, [(Rule::statement, "a;"), (Rule::error, "b?;"), (Rule::statement, "c;")]);
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    pub fn recover<F, G>(self: Box<Self>, rule: R, f: F, mut sync: G) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+        G: FnMut(Box<Self>) -> Either<Box<Self>>,
+    {
+        if !self.recovery || self.lookahead != Lookahead::None {
+            return f(self);
+        }
+        let initial = self.position;
+        let index = self.queue.len();
+        let mut state = match f(self) {
+            Ok(state) => return Ok(state),
+            Err(state) => state,
+        };
+        state.committed = false;
+        state.position = initial;
+        state.queue.truncate(index);
+        if state.position.at_end() {
+            return Err(state);
+        }
+        let error = state.parsing_error();
+        state.errors.push(error);
+        state.position.skip(1);
+        while !state.position.at_end() {
+            let position = state.position;
+            state = match sync(state) {
+                Ok(s) => {
+                    state = s;
+                    break;
+                }
+                Err(s) => s,
+            };
+            state.position = position;
+            state.queue.truncate(index);
+            state.position.skip(1);
+        }
+        // the tokens of `sync` are part of the error
+        state.queue.truncate(index);
+        state.queue.push(TokenQueue::Start { end_token_index: index + 1, input_offset: initial.offset() });
+        state.queue.push(TokenQueue::End { start_token_index: index, rule, tag: None, input_offset: state.position.offset() });
+        state.pos_attempts.clear();
+        state.neg_attempts.clear();
+        state.literals.clear();
+        state.labels.clear();
+        state.message = None;
+        state.attempt_pos = 0;
+        Ok(state)
+    }
+
+    /// Match `f`, when it fails without any progress, report `label` instead of everything `f` attempted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     number,
+    /// }
+    /// let error = state("x", |s| {
+    ///     s.label("expression", |s| {
+    ///         s.match_string("(", false)
+    ///             .or_else(|s| s.rule(Rule::number, |s| s.match_range('0'..'9')))
+    ///     })
+    /// })
+    /// .unwrap_err();
+    /// assert_eq!(error.variant.message(), "expected expression, found `x`");
+    /// ```
+    pub fn label<F>(self: Box<Self>, label: &str, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+    {
+        if self.lookahead == Lookahead::Negative {
+            return f(self);
+        }
+        let offset = self.position.offset();
+        let (pos_index, neg_index, literal_index, label_index) = self.attempts_index(offset);
+        match f(self) {
+            Err(mut state) if state.attempt_pos <= offset => {
+                state.forward_attempts(offset);
+                state.pos_attempts.truncate(pos_index);
+                state.neg_attempts.truncate(neg_index);
+                state.literals.truncate(literal_index);
+                state.labels.truncate(label_index);
+                state.labels.push(label.to_string());
+                state.message = None;
+                Err(state)
+            }
+            result => result,
+        }
+    }
+
+    /// Match `f`, when the furthest failure is inside `f`, report `message` instead of the expected items.
+    ///
+    /// The innermost message wins, a message is forgotten once anything else is attempted at the same position or
+    /// further, such as the next branch of a choice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     block,
+    /// }
+    /// let error = state("{a", |s| {
+    ///     s.rule(Rule::block, |s| {
+    ///         s.match_string("{", false)
+    ///             .and_then(|s| s.match_range('a'..'z'))
+    ///             .and_then(|s| s.error("expected a closing brace", |s| s.match_string("}", false)))
+    ///     })
+    /// })
+    /// .unwrap_err();
+    /// assert_eq!(error.variant.message(), "expected a closing brace");
+    /// ```
+    pub fn error<F>(self: Box<Self>, message: &str, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+    {
+        if self.lookahead == Lookahead::Negative {
+            return f(self);
+        }
+        let offset = self.position.offset();
+        let (furthest, attempts) = (self.attempt_pos, self.attempts_at(self.attempt_pos));
+        match f(self) {
+            Err(mut state) => {
+                state.forward_attempts(offset);
+                // the failure may come from an earlier sibling, which went further than `f`
+                let inside = state.attempt_pos > furthest || state.attempts_at(furthest) > attempts;
+                if inside && state.message.is_none() {
+                    state.message = Some(message.to_string());
+                }
+                Err(state)
+            }
+            result => result,
+        }
+    }
+
+    /// Build the error from the attempts at the furthest position.
+    fn parsing_error(&mut self) -> YggdrasilError<R> {
+        self.pos_attempts.sort();
+        self.pos_attempts.dedup();
+        self.neg_attempts.sort();
+        self.neg_attempts.dedup();
+        let mut literals = self.literals.iter().chain(&self.labels).cloned().collect::<Vec<_>>();
+        literals.sort();
+        literals.dedup();
+        let variant = match &self.message {
+            Some(message) => ErrorKind::CustomError { message: message.clone() },
+            None => ErrorKind::ParsingError {
+                positives: self.pos_attempts.clone(),
+                negatives: self.neg_attempts.clone(),
+                literals,
+                found: self.input[self.attempt_pos..].chars().next(),
+            },
+        };
+        // TODO(performance): Guarantee state.attempt_pos is a valid position
+        YggdrasilError::new_from_offset(variant, position::Position::new(self.input, self.attempt_pos).unwrap())
+    }
+
     /// Tag current node
     ///
     /// # Examples
@@ -310,38 +832,249 @@ where
         S: Into<String>,
     {
         if let Some(TokenQueue::End { tag: old, .. }) = self.queue.last_mut() {
-            *old = Some(Cow::Owned(tag.into()))
+            *old = Some(tag.into())
         }
         Ok(self)
     }
+
+    /// Returns the index of the next token in the queue, used to mark where an operand starts.
+    #[inline]
+    pub fn token_index(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Precedence climbing step for postfix and infix operators.
+    ///
+    /// Runs `f` to match the operator (and the right operand if any) behind the left operand which
+    /// starts at token `start`. On success, the left operand is wrapped into a `rule` node tagged
+    /// `lhs`, then all tokens since `start` are wrapped into a `rule` node tagged `tag`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, Either, State, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     expr,
+    ///     number,
+    /// }
+    /// fn number(state: Box<State<Rule>>) -> Either<Box<State<Rule>>> {
+    ///     state.rule(Rule::number, |s| s.match_range('0'..'9')).and_then(|s| s.tag_node("number"))
+    /// }
+    ///
+    /// let input = "1+2";
+    /// let pairs = state(input, |s| {
+    ///     let start = s.token_index();
+    ///     number(s).and_then(|s| {
+    ///         s.climb(start, Rule::expr, "lhs", "add", |s| {
+    ///             s.match_string("+", false)
+    ///                 .and_then(|s| s.rule(Rule::expr, number))
+    ///                 .and_then(|s| s.tag_node("rhs"))
+    ///         })
+    ///     })
+    /// })
+    /// .unwrap();
+    /// let add = pairs.into_iter().next().unwrap();
+    /// assert_eq!(add.get_tag(), Some("add"));
+    /// assert_eq!(add.find_first_tag("lhs").unwrap().as_str(), "1");
+    /// assert_eq!(add.find_first_tag("rhs").unwrap().as_str(), "2");
+    /// ```
+    #[inline]
+    #[cfg(not(feature = "dynamic"))]
+    pub fn climb<F>(self: Box<Self>, start: usize, rule: R, lhs: &'static str, tag: &'static str, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+    {
+        let end = self.queue.len();
+        let mut state = self.sequence(f)?;
+        if state.lookahead == Lookahead::None {
+            let lhs_end = state.wrap_tokens(start, end, rule.clone());
+            state.retag(lhs_end, lhs);
+            let new_end = state.wrap_tokens(start, state.queue.len(), rule);
+            state.retag(new_end, tag);
+        }
+        Ok(state)
+    }
+    /// Do not use this method except within a vm
+    #[inline]
+    #[cfg(feature = "dynamic")]
+    pub fn climb<F, S>(self: Box<Self>, start: usize, rule: R, lhs: S, tag: S, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+        S: Into<String>,
+    {
+        let end = self.queue.len();
+        let mut state = self.sequence(f)?;
+        if state.lookahead == Lookahead::None {
+            let lhs_end = state.wrap_tokens(start, end, rule.clone());
+            state.retag(lhs_end, lhs.into());
+            let new_end = state.wrap_tokens(start, state.queue.len(), rule);
+            state.retag(new_end, tag.into());
+        }
+        Ok(state)
+    }
+    /// Wrap the finished tokens in `start..end` into a new `rule` node, returns the index of its end token.
+    fn wrap_tokens(&mut self, start: usize, end: usize, rule: R) -> usize {
+        let start_offset = match self.queue.get(start) {
+            Some(TokenQueue::Start { input_offset, .. } | TokenQueue::End { input_offset, .. }) => *input_offset,
+            None => self.position.offset(),
+        };
+        let end_offset = match end.checked_sub(1).and_then(|i| self.queue.get(i)) {
+            Some(TokenQueue::End { input_offset, .. }) if end > start => *input_offset,
+            _ => start_offset,
+        };
+        // every token after `start` is closed, so only their links need to be shifted
+        let shift = |index: usize| index + (index >= start) as usize + (index >= end) as usize;
+        for token in &mut self.queue[start..] {
+            match token {
+                TokenQueue::Start { end_token_index, .. } => *end_token_index = shift(*end_token_index),
+                TokenQueue::End { start_token_index, .. } => *start_token_index = shift(*start_token_index),
+            }
+        }
+        self.queue.insert(end, TokenQueue::End { start_token_index: start, input_offset: end_offset, rule, tag: None });
+        self.queue.insert(start, TokenQueue::Start { end_token_index: end + 1, input_offset: start_offset });
+        end + 1
+    }
+    #[cfg(not(feature = "dynamic"))]
+    fn retag(&mut self, index: usize, tag: &'static str) {
+        if let Some(TokenQueue::End { tag: old, .. }) = self.queue.get_mut(index) {
+            *old = Some(tag)
+        }
+    }
+    #[cfg(feature = "dynamic")]
+    fn retag(&mut self, index: usize, tag: String) {
+        if let Some(TokenQueue::End { tag: old, .. }) = self.queue.get_mut(index) {
+            *old = Some(tag)
+        }
+    }
     fn attempts_at(&self, pos: usize) -> usize {
-        if self.attempt_pos == pos { self.pos_attempts.len() + self.neg_attempts.len() } else { 0 }
+        match self.attempt_pos == pos {
+            true => self.pos_attempts.len() + self.neg_attempts.len() + self.literals.len() + self.labels.len(),
+            false => 0,
+        }
+    }
+
+    fn attempts_index(&self, pos: usize) -> (usize, usize, usize, usize) {
+        match self.attempt_pos == pos {
+            true => (self.pos_attempts.len(), self.neg_attempts.len(), self.literals.len(), self.labels.len()),
+            // Attempts have not been cleared yet since the attempt_pos is older.
+            false => (0, 0, 0, 0),
+        }
+    }
+
+    /// Forget the attempts before `offset`
+    fn forward_attempts(&mut self, offset: usize) {
+        if offset > self.attempt_pos {
+            self.pos_attempts.clear();
+            self.neg_attempts.clear();
+            self.literals.clear();
+            self.labels.clear();
+            self.message = None;
+            self.attempt_pos = offset;
+        }
     }
 
-    fn track(&mut self, rule: R, offset: usize, pos_attempts_index: usize, neg_attempts_index: usize, prev_attempts: usize) {
+    fn track(&mut self, rule: R, offset: usize, index: (usize, usize, usize, usize), prev_attempts: usize) {
+        let (pos_index, neg_index, literal_index, label_index) = index;
         // If nested rules made no progress, there is no use to report them; it's only useful to
         // track the current rule, the exception being when only one attempt has been made during
         // the children rules.
         let curr_attempts = self.attempts_at(offset);
         if curr_attempts > prev_attempts && curr_attempts - prev_attempts == 1 {
-            return;
+            // the built-in rules report their literal, the other rules report themselves instead of the literal
+            // labels are always kept
+            let literal = self.literals.len() > literal_index;
+            let label = self.labels.len() > label_index;
+            if rule.is_literal() || ((label || !literal) && !rule.is_ignore()) {
+                return;
+            }
         }
 
         if offset == self.attempt_pos {
-            self.pos_attempts.truncate(pos_attempts_index);
-            self.neg_attempts.truncate(neg_attempts_index);
+            self.pos_attempts.truncate(pos_index);
+            self.neg_attempts.truncate(neg_index);
+            self.literals.truncate(literal_index);
+            self.labels.truncate(label_index);
         }
 
-        if offset > self.attempt_pos {
-            self.pos_attempts.clear();
-            self.neg_attempts.clear();
-            self.attempt_pos = offset;
+        // spaces and comments are never expected
+        if rule.is_ignore() {
+            return;
         }
 
+        self.forward_attempts(offset);
+
         let attempts = if self.lookahead != Lookahead::Negative { &mut self.pos_attempts } else { &mut self.neg_attempts };
 
         if offset == self.attempt_pos {
             attempts.push(rule);
+            self.message = None;
+        }
+    }
+
+    /// Record the text or regex that failed to match at the current position
+    fn track_literal<F>(&mut self, literal: F)
+    where
+        F: FnOnce() -> String,
+    {
+        let offset = self.position.offset();
+        if self.lookahead == Lookahead::Negative || offset < self.attempt_pos {
+            return;
+        }
+        self.forward_attempts(offset);
+        self.literals.push(literal());
+        self.message = None;
+    }
+
+    /// Commit the enclosing sequence: when anything after the cut fails, the failure is final. The choices,
+    /// optionals and repeats around it don't try anything else, only [`State::recover`] goes on.
+    ///
+    /// The parser rarely comes back before a cut, so the results memorized before it are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{state, State, YggdrasilRule};
+    /// # impl YggdrasilRule for Rule {}
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {
+    ///     statement,
+    /// }
+    /// // statement = 'if' @cut() [a-z] | [a-z]+
+    /// let error = state("if1", |s| {
+    ///     s.rule(Rule::statement, |s| {
+    ///         s.sequence(|s| {
+    ///             s.match_string("if", false)
+    ///                 .and_then(|s| s.cut())
+    ///                 .and_then(|s| s.match_range('a'..'z'))
+    ///         })
+    ///         .or_else(|s| s.alternative(|s| s.repeat(1..9, |s| s.match_range('a'..'z'))))
+    ///     })
+    /// })
+    /// .unwrap_err();
+    /// assert_eq!(error.variant.message(), "expected `a`..=`z`, found `1`");
+    /// ```
+    pub fn cut(mut self: Box<Self>) -> Either<Box<Self>> {
+        self.cut = true;
+        if self.lookahead == Lookahead::None {
+            let offset = self.position.offset();
+            self.memo.retain(|(_, start), _| *start >= offset);
+        }
+        Ok(self)
+    }
+
+    /// Try the next branch of a choice, unless the previous branch failed after a [`State::cut`].
+    #[inline]
+    pub fn alternative<F>(self: Box<Self>, f: F) -> Either<Box<Self>>
+    where
+        F: FnOnce(Box<Self>) -> Either<Box<Self>>,
+    {
+        match self.committed {
+            true => Err(self),
+            false => f(self),
         }
     }
 
@@ -385,21 +1118,28 @@ where
     /// assert_eq!(pairs.len(), 0);
     /// ```
     #[inline]
-    pub fn sequence<F>(self: Box<Self>, f: F) -> Either<Box<Self>>
+    pub fn sequence<F>(mut self: Box<Self>, f: F) -> Either<Box<Self>>
     where
         F: FnOnce(Box<Self>) -> Either<Box<Self>>,
     {
         let token_index = self.queue.len();
         let initial = self.position;
+        let outer_cut = core::mem::replace(&mut self.cut, false);
 
         let result = f(self);
 
         match result {
-            Ok(new) => Ok(new),
+            Ok(mut new) => {
+                new.cut = outer_cut;
+                Ok(new)
+            }
             Err(mut new) => {
                 // Restore the initial position and truncate the token queue.
                 new.position = initial;
                 new.queue.truncate(token_index);
+                // a failure after a cut is final
+                new.committed |= new.cut;
+                new.cut = outer_cut;
                 Err(new)
             }
         }
@@ -440,6 +1180,7 @@ where
         loop {
             match result {
                 Ok(state) => result = f(state),
+                Err(state) if state.committed => return Err(state),
                 Err(state) => {
                     result = Ok(state);
                     break;
@@ -496,6 +1237,7 @@ where
         F: FnOnce(Box<Self>) -> Either<Box<Self>>,
     {
         match f(self) {
+            Err(state) if state.committed => Err(state),
             Ok(state) | Err(state) => Ok(state),
         }
     }
@@ -574,23 +1316,16 @@ where
     /// ```
     #[inline]
     pub fn match_string(mut self: Box<Self>, string: &str, insensitive: bool) -> Either<Box<Self>> {
-        match insensitive {
-            true => {
-                if self.position.match_insensitive(string) {
-                    Ok(self)
-                }
-                else {
-                    Err(self)
-                }
-            }
-            false => {
-                if self.position.match_string(string) {
-                    Ok(self)
-                }
-                else {
-                    Err(self)
-                }
-            }
+        let matched = match insensitive {
+            true => self.position.match_insensitive(string),
+            false => self.position.match_string(string),
+        };
+        if matched {
+            Ok(self)
+        }
+        else {
+            self.track_literal(|| format!("`{}`", string.escape_debug()));
+            Err(self)
         }
     }
 
@@ -630,11 +1365,19 @@ where
     /// ```
     #[inline]
     pub fn match_range(mut self: Box<Self>, range: Range<char>) -> Either<Box<Self>> {
-        if self.position.match_range(range) { Ok(self) } else { Err(self) }
+        if self.position.match_range(range.clone()) {
+            Ok(self)
+        }
+        else {
+            self.track_literal(|| format!("`{}`..=`{}`", range.start.escape_debug(), range.end.escape_debug()));
+            Err(self)
+        }
     }
     /// Attempts to match a single character from the given range. Returns `Ok` with the updated
     /// `Box<ParserState>` if successful, or `Err` with the updated `Box<ParserState>` otherwise.
     ///
+    /// The `raw` pattern is shown in the errors, the compiled regex doesn't keep it.
+    ///
     /// # Caution
     /// The provided `range` is interpreted as inclusive.
     ///
@@ -667,8 +1410,77 @@ where
     /// assert_eq!(result.unwrap_err().position().pos(), 0);
     /// ```
     #[inline]
-    pub fn match_regex(mut self: Box<Self>, regex: &crate::Regex) -> Either<Box<Self>> {
-        if self.position.match_regex(regex) { Ok(self) } else { Err(self) }
+    pub fn match_regex(mut self: Box<Self>, regex: &crate::Regex, raw: &str) -> Either<Box<Self>> {
+        if self.position.match_regex(regex) {
+            Ok(self)
+        }
+        else {
+            self.track_literal(|| format!("/{raw}/"));
+            Err(self)
+        }
+    }
+
+    /// Attempts to match a regex precompiled by the compiler, the regex must start with `^`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{RegexCompiled, State};
+    /// # use regex_automata::dfa::regex::Regex;
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {}
+    /// # impl yggdrasil_rt::YggdrasilRule for Rule {}
+    /// # fn leak((bytes, pad): (Vec<u8>, usize)) -> &'static [u8] {
+    /// #     &bytes.leak()[pad..]
+    /// # }
+    ///
+    /// let regex = Regex::new("^([a-z]+)").unwrap();
+    /// let compiled = RegexCompiled {
+    ///     raw: "[a-z]+",
+    ///     forward_le: leak(regex.forward().to_bytes_little_endian()),
+    ///     reverse_le: leak(regex.reverse().to_bytes_little_endian()),
+    ///     forward_be: leak(regex.forward().to_bytes_big_endian()),
+    ///     reverse_be: leak(regex.reverse().to_bytes_big_endian()),
+    /// };
+    /// let state: Box<State<'_, Rule>> = State::new("abc1");
+    /// assert_eq!(state.match_compiled(&compiled).unwrap().position().offset(), 3);
+    /// ```
+    #[inline]
+    pub fn match_compiled(mut self: Box<Self>, regex: &crate::RegexCompiled) -> Either<Box<Self>> {
+        if self.position.match_compiled(regex) {
+            Ok(self)
+        }
+        else {
+            self.track_literal(|| format!("/{}/", regex.raw));
+            Err(self)
+        }
+    }
+
+    /// Attempts to match the first literal of the `trie` that the rest of the input starts with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use yggdrasil_rt::{State, TextTrie};
+    /// # #[allow(non_camel_case_types)]
+    /// # #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    /// enum Rule {}
+    /// # impl yggdrasil_rt::YggdrasilRule for Rule {}
+    ///
+    /// let trie = TextTrie::new(&["if", "in"], true);
+    /// let state: Box<State<'_, Rule>> = State::new("IN");
+    /// assert_eq!(state.match_trie(&trie).unwrap().position().offset(), 2);
+    /// ```
+    #[inline]
+    pub fn match_trie(mut self: Box<Self>, trie: &crate::TextTrie) -> Either<Box<Self>> {
+        if self.position.match_trie(trie) {
+            return Ok(self);
+        }
+        for text in trie.texts() {
+            self.track_literal(|| format!("`{}`", text.escape_debug()));
+        }
+        Err(self)
     }
 
     /// Attempts to skip `n` characters forward. Returns `Ok` with the updated `Box<ParserState>`
@@ -805,8 +1617,14 @@ where
     /// assert!(result.is_ok());
     /// ```
     #[inline]
-    pub fn end_of_input(self: Box<Self>) -> Either<Box<Self>> {
-        if self.position.at_end() { Ok(self) } else { Err(self) }
+    pub fn end_of_input(mut self: Box<Self>) -> Either<Box<Self>> {
+        if self.position.at_end() {
+            Ok(self)
+        }
+        else {
+            self.track_literal(|| "end of input".to_string());
+            Err(self)
+        }
     }
 
     /// Starts a lookahead transformation provided by `f` from the `Box<ParserState>`. It returns
@@ -863,18 +1681,23 @@ where
         };
 
         let initial_pos = self.position;
+        let initial_cut = self.cut;
 
         let result = f(self.checkpoint());
 
+        // the cuts inside never escape
         let result_state = match result {
             Ok(mut new_state) => {
                 new_state.position = initial_pos;
                 new_state.lookahead = initial_lookahead;
+                new_state.cut = initial_cut;
                 Ok(new_state.restore())
             }
             Err(mut new_state) => {
                 new_state.position = initial_pos;
                 new_state.lookahead = initial_lookahead;
+                new_state.cut = initial_cut;
+                new_state.committed = false;
                 Err(new_state.restore())
             }
         };
@@ -446,6 +446,28 @@ impl<'i> Position<'i> {
         }
     }
 
+    #[inline]
+    pub(crate) fn match_compiled(&mut self, regex: &crate::RegexCompiled) -> bool {
+        match regex.regex().find(self.rest_text()) {
+            Some(s) => {
+                self.position += s.end();
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn match_trie(&mut self, trie: &crate::TextTrie) -> bool {
+        match trie.find(self.rest_text()) {
+            Some(length) => {
+                self.position += length;
+                true
+            }
+            None => false,
+        }
+    }
+
     fn rest_text(&self) -> &str {
         &self.input[self.position..]
     }
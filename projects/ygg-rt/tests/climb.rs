@@ -0,0 +1,93 @@
+use yggdrasil_rt::{state, Either, State, TokenPair, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    expr,
+    number,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn test() {
+    assert_eq!(climb("1"), "1");
+    assert_eq!(climb("1+2+3"), "(add (add 1 2) 3)");
+    assert_eq!(climb("1^2^3"), "(pow 1 (pow 2 3))");
+    assert_eq!(climb("1+2^3+4"), "(add (add 1 (pow 2 3)) 4)");
+    assert_eq!(climb("-1+2"), "(add (neg 1) 2)");
+    assert_eq!(climb("-1^2"), "(neg (pow 1 2))");
+}
+
+fn climb(input: &str) -> String {
+    let mut tree = state(input, parse_expr).unwrap();
+    show(tree.next().unwrap())
+}
+
+fn show(pair: TokenPair<Rule>) -> String {
+    let variant = pair.into_inner().next().unwrap();
+    match variant.get_tag() {
+        Some("atom") => variant.as_str().to_string(),
+        Some("neg") => format!("(neg {})", show(variant.find_first_tag("x").unwrap())),
+        Some(tag) => {
+            format!("({} {} {})", tag, show(variant.find_first_tag("x").unwrap()), show(variant.find_first_tag("y").unwrap()))
+        }
+        None => unreachable!(),
+    }
+}
+
+fn parse_expr(state: Input) -> Output {
+    state.rule(Rule::expr, |s| climb_expr(s, 0))
+}
+
+/// ```ygg
+/// climb Expr {
+///     | Number             #Atom
+///     | x:Self '^' y:Self  #Pow >
+///     | '-' x:Self         #Neg
+///     | x:Self '+' y:Self  #Add
+/// }
+/// ```
+fn climb_expr(state: Input, min: u32) -> Output {
+    let start = state.token_index();
+    let mut state = Err(state)
+        .or_else(|s| {
+            s.rule(Rule::expr, |s| {
+                s.match_string("-", false).and_then(|s| s.rule(Rule::expr, |s| climb_expr(s, 2)).and_then(|s| s.tag_node("x")))
+            })
+            .and_then(|s| s.tag_node("neg"))
+        })
+        .or_else(|s| {
+            s.rule(Rule::expr, |s| s.rule(Rule::number, |s| s.match_range('0'..'9'))).and_then(|s| s.tag_node("atom"))
+        })?;
+    loop {
+        state = match Err(state)
+            .or_else(|s| {
+                if 3 >= min {
+                    s.climb(start, Rule::expr, "x", "pow", |s| {
+                        s.match_string("^", false)
+                            .and_then(|s| s.rule(Rule::expr, |s| climb_expr(s, 3)).and_then(|s| s.tag_node("y")))
+                    })
+                }
+                else {
+                    Err(s)
+                }
+            })
+            .or_else(|s| {
+                if 1 >= min {
+                    s.climb(start, Rule::expr, "x", "add", |s| {
+                        s.match_string("+", false)
+                            .and_then(|s| s.rule(Rule::expr, |s| climb_expr(s, 2)).and_then(|s| s.tag_node("y")))
+                    })
+                }
+                else {
+                    Err(s)
+                }
+            }) {
+            Ok(s) => s,
+            Err(s) => return Ok(s),
+        }
+    }
+}
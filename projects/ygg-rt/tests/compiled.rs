@@ -0,0 +1,68 @@
+use regex_automata::dfa::regex::Regex;
+use yggdrasil_rt::{state, Either, RegexAligned, RegexCompiled, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    word,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn native_endian() {
+    let regex = Regex::new("^([a-z]+)").unwrap();
+    let compiled = compile(&regex);
+    let expected = regex.find("abc def").map(|m| m.end());
+    assert_eq!(compiled.regex().find("abc def").map(|m| m.end()), expected);
+    assert_eq!(word("hello world"), Some("hello".to_string()));
+    assert_eq!(word("42"), None);
+}
+
+#[test]
+fn both_endian() {
+    let regex = Regex::new("^([a-z]+)").unwrap();
+    let compiled = compile(&regex);
+    // the serialized forms only differ in the byte order
+    assert_eq!(compiled.forward_le.len(), compiled.forward_be.len());
+    assert_eq!(compiled.reverse_le.len(), compiled.reverse_be.len());
+    assert_ne!(compiled.forward_le, compiled.reverse_le);
+}
+
+#[test]
+fn aligned() {
+    const BYTES: &[u8] = &RegexAligned { align: [], bytes: [1u8, 2, 3] }.bytes;
+    assert_eq!(BYTES.as_ptr() as usize % 4, 0);
+    assert_eq!(BYTES, [1, 2, 3]);
+}
+
+fn compile(regex: &Regex) -> RegexCompiled {
+    RegexCompiled {
+        raw: "",
+        forward_le: leak(regex.forward().to_bytes_little_endian()),
+        reverse_le: leak(regex.reverse().to_bytes_little_endian()),
+        forward_be: leak(regex.forward().to_bytes_big_endian()),
+        reverse_be: leak(regex.reverse().to_bytes_big_endian()),
+    }
+}
+
+fn leak((bytes, pad): (Vec<u8>, usize)) -> &'static [u8] {
+    &bytes.leak()[pad..]
+}
+
+fn word(input: &str) -> Option<String> {
+    let tree = state(input, parse_word).ok()?;
+    tree.into_iter().next().map(|pair| pair.as_str().to_string())
+}
+
+/// ```ygg
+/// class Word {
+///     /[a-z]+/
+/// }
+/// ```
+fn parse_word(state: Input) -> Output {
+    static REGEX: std::sync::OnceLock<RegexCompiled> = std::sync::OnceLock::new();
+    state.rule(Rule::word, |s| s.match_compiled(REGEX.get_or_init(|| compile(&Regex::new("^([a-z]+)").unwrap()))))
+}
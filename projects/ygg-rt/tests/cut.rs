@@ -0,0 +1,106 @@
+use yggdrasil_rt::{state, state_recover, Either, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    program,
+    statement,
+    if_statement,
+    identifier,
+    error,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn committed() {
+    assert!(state("ifx;", parse_program).is_ok());
+    assert!(state("iffy;", parse_program).is_err());
+    assert!(state("a;ifx;b;", parse_program).is_ok());
+}
+
+#[test]
+fn final_failure() {
+    // without the cut, `if` is an identifier as well, and `;` would be expected too
+    let error = state("a;if1;", parse_program).unwrap_err();
+    assert_eq!(error.variant.message(), "expected `a`..=`z`, found `1`");
+}
+
+#[test]
+fn lookahead() {
+    // the cut inside the lookahead doesn't commit the choice around it
+    let result = state("if1", |s| s.lookahead(true, parse_if_statement).or_else(|s| s.alternative(parse_identifier)));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn recovered() {
+    let (tree, errors) = state_recover("a;if1;b;", parse_program);
+    let nodes: Vec<_> = tree.flatten().filter(|pair| pair.get_rule() != Rule::identifier).map(|pair| pair.get_rule()).collect();
+    assert_eq!(nodes, [Rule::program, Rule::statement, Rule::error, Rule::statement]);
+    assert_eq!(errors.len(), 1);
+}
+
+/// ```ygg
+/// class Program {
+///     (Statement ';')* EOI
+/// }
+/// ```
+fn parse_program(state: Input) -> Output {
+    state.rule(Rule::program, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        s.sequence(|s| Ok(s).and_then(|s| parse_statement(s)).and_then(|s| s.match_string(";", false)))
+                    })
+                })
+                .and_then(|s| s.end_of_input())
+        })
+    })
+}
+
+/// ```ygg
+/// #recover(';')
+/// union Statement {
+///     | IfStatement
+///     | Identifier
+/// }
+/// ```
+fn parse_statement(state: Input) -> Output {
+    state.recover(
+        Rule::error,
+        |s| {
+            s.rule(Rule::statement, |s| {
+                Err(s).or_else(|s| s.alternative(parse_if_statement)).or_else(|s| s.alternative(parse_identifier))
+            })
+        },
+        |s| s.lookahead(true, |s| s.match_string(";", false)),
+    )
+}
+
+/// ```ygg
+/// class IfStatement {
+///     'if' @cut() [a-z]
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_if_statement(state: Input) -> Output {
+    state.rule(Rule::if_statement, |s| {
+        s.sequence(|s| {
+            Ok(s).and_then(|s| s.match_string("if", false)).and_then(|s| s.cut()).and_then(|s| s.match_range('a'..'z'))
+        })
+    })
+}
+
+/// ```ygg
+/// class Identifier {
+///     [a-z]+
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_identifier(state: Input) -> Output {
+    state.rule(Rule::identifier, |s| s.repeat(1..u32::MAX, |s| s.match_range('a'..'z')))
+}
@@ -0,0 +1,122 @@
+use yggdrasil_rt::{state, Either, Regex, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {
+    fn is_ignore(&self) -> bool {
+        matches!(self, Self::white_space | Self::IgnoreText)
+    }
+    fn is_literal(&self) -> bool {
+        matches!(self, Self::IgnoreText)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    block,
+    identifier,
+    list,
+    white_space,
+    IgnoreText,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn literals() {
+    // the identifier could go on as well
+    assert_eq!(message("a=", parse_list), "expected `,`, `;`, `a`..=`z` or `}`, found `=`");
+    assert_eq!(message("a,", parse_list), "expected identifier, found end of input");
+}
+
+#[test]
+fn regexes() {
+    let regex = Regex::new("^([0-9]+)").unwrap();
+    let message = |input| state(input, |s: Input| s.match_regex(&regex, "[0-9]+")).unwrap_err().variant.message().to_string();
+    assert_eq!(message("a"), "expected /[0-9]+/, found `a`");
+}
+
+#[test]
+fn rules() {
+    assert_eq!(message("{a;=", parse_block), "expected `}` or identifier, found `=`");
+    assert_eq!(message("{a; =", parse_block), "expected `}` or identifier, found `=`");
+    assert_eq!(message("{a;", parse_block), "expected `}` or identifier, found end of input");
+    assert_eq!(message("1", parse_block), "expected block, found `1`");
+}
+
+fn message(input: &str, f: fn(Input) -> Output) -> String {
+    state(input, f).unwrap_err().variant.message().to_string()
+}
+
+fn builtin_text<'i>(state: Input<'i>, text: &'static str, case: bool) -> Output<'i> {
+    state.rule(Rule::IgnoreText, |s| s.match_string(text, case))
+}
+
+fn builtin_ignore(state: Input) -> Output {
+    state.repeat(0..u32::MAX, parse_white_space)
+}
+
+/// ```ygg
+/// class Block {
+///     '{' (Identifier ';')* '}'
+/// }
+/// ```
+fn parse_block(state: Input) -> Output {
+    state.rule(Rule::block, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| builtin_text(s, "{", false))
+                .and_then(|s| builtin_ignore(s))
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| parse_identifier(s))
+                                .and_then(|s| builtin_ignore(s))
+                                .and_then(|s| builtin_text(s, ";", false))
+                                .and_then(|s| builtin_ignore(s))
+                        })
+                    })
+                })
+                .and_then(|s| builtin_text(s, "}", false))
+        })
+    })
+}
+
+/// ```ygg
+/// class List {
+///     Identifier (',' Identifier)* (';' | '}')
+/// }
+/// ```
+fn parse_list(state: Input) -> Output {
+    state.rule(Rule::list, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| parse_identifier(s))
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        s.sequence(|s| Ok(s).and_then(|s| builtin_text(s, ",", false)).and_then(|s| parse_identifier(s)))
+                    })
+                })
+                .and_then(|s| builtin_text(s, ";", false).or_else(|s| builtin_text(s, "}", false)))
+        })
+    })
+}
+
+/// ```ygg
+/// class Identifier {
+///     [a-z]+
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_identifier(state: Input) -> Output {
+    state.rule(Rule::identifier, |s| s.repeat(1..u32::MAX, |s| s.match_range('a'..'z')))
+}
+
+/// ```ygg
+/// ignore class WhiteSpace {
+///     ' '
+/// }
+/// ```
+fn parse_white_space(state: Input) -> Output {
+    state.rule(Rule::white_space, |s| s.match_string(" ", false))
+}
@@ -0,0 +1,129 @@
+use yggdrasil_rt::{state, AnsiHighlighter, Either, HtmlHighlighter, State, StyledText, YggdrasilRule};
+
+impl YggdrasilRule for Rule {
+    fn is_ignore(&self) -> bool {
+        matches!(self, Self::white_space | Self::comment)
+    }
+    fn get_style(&self) -> &'static str {
+        match self {
+            Self::keyword => "keyword",
+            Self::string => "string",
+            Self::escape => "escape",
+            Self::comment => "comment",
+            _ => "",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    program,
+    keyword,
+    identifier,
+    string,
+    escape,
+    white_space,
+    comment,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn spans() {
+    let text = highlight("let a = \"b\\n\" # c");
+    let spans: Vec<_> = text.spans().iter().map(|span| (span.style, span.text)).collect();
+    assert_eq!(
+        spans,
+        [
+            ("keyword", "let"),
+            ("", " a = "),
+            ("string", "\"b"),
+            ("escape", "\\n"),
+            ("string", "\""),
+            ("", " "),
+            ("comment", "# c"),
+        ]
+    );
+    assert_eq!(text.spans()[2].range, 8..10);
+}
+
+#[test]
+fn ansi() {
+    let text = highlight("let a = # c");
+    assert_eq!(AnsiHighlighter::default().render(&text), "\x1b[35mlet\x1b[0m a = \x1b[2;3m# c\x1b[0m");
+    let highlighter = AnsiHighlighter::default().with_style("keyword", "\x1b[1m");
+    assert_eq!(highlighter.render(&text), "\x1b[1mlet\x1b[0m a = \x1b[2;3m# c\x1b[0m");
+}
+
+#[test]
+fn html() {
+    let text = highlight("let a = \"<b>\"");
+    assert_eq!(
+        HtmlHighlighter::default().render(&text),
+        "<span class=\"ygg-keyword\">let</span> a = <span class=\"ygg-string\">&quot;&lt;b&gt;&quot;</span>"
+    );
+}
+
+fn highlight(input: &str) -> StyledText<'_> {
+    StyledText::from(state(input, parse_program).unwrap())
+}
+
+/// ```ygg
+/// class Program {
+///     ('let' | Identifier | '=' | String | WhiteSpace | Comment)* EOI
+/// }
+/// ```
+fn parse_program(state: Input) -> Output {
+    state.rule(Rule::program, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        Err(s)
+                            .or_else(|s| s.rule(Rule::keyword, |s| s.match_string("let", false)))
+                            .or_else(parse_identifier)
+                            .or_else(|s| s.match_string("=", false))
+                            .or_else(parse_string)
+                            .or_else(|s| s.rule(Rule::white_space, |s| s.match_string(" ", false)))
+                            .or_else(|s| {
+                                s.rule(Rule::comment, |s| s.match_string("#", false).and_then(|s| s.skip_until(&["\n"])))
+                            })
+                    })
+                })
+                .and_then(|s| s.end_of_input())
+        })
+    })
+}
+
+/// ```ygg
+/// class Identifier {
+///     [a-z]+
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_identifier(state: Input) -> Output {
+    state.rule(Rule::identifier, |s| s.repeat(1..u32::MAX, |s| s.match_range('a'..'z')))
+}
+
+/// ```ygg
+/// class String {
+///     '"' (Escape | [^"\\])* '"'
+/// }
+/// ```
+fn parse_string(state: Input) -> Output {
+    state.rule(Rule::string, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| s.match_string("\"", false))
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        Err(s)
+                            .or_else(|s| s.rule(Rule::escape, |s| s.match_string("\\", false).and_then(|s| s.skip(1))))
+                            .or_else(|s| s.match_char_if(|c| c != '"' && c != '\\'))
+                    })
+                })
+                .and_then(|s| s.match_string("\"", false))
+        })
+    })
+}
@@ -0,0 +1,112 @@
+use yggdrasil_rt::{state, Either, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {
+    fn is_ignore(&self) -> bool {
+        matches!(self, Self::IgnoreText)
+    }
+    fn is_literal(&self) -> bool {
+        matches!(self, Self::IgnoreText)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    block,
+    expression_hard,
+    identifier,
+    number,
+    IgnoreText,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn labels() {
+    assert_eq!(message("=", parse_expression_hard), "expected expression, found `=`");
+    // the outer expression made progress, the inner one is labelled
+    assert_eq!(message("(=", parse_expression_hard), "expected expression, found `=`");
+    // the identifier could go on as well
+    assert_eq!(message("(a=", parse_expression_hard), "expected `)` or `a`..=`z`, found `=`");
+}
+
+#[test]
+fn errors() {
+    assert_eq!(message("{a", parse_block), "expected a closing brace");
+    assert_eq!(message("{a=", parse_block), "expected a closing brace");
+    assert_eq!(message("{=", parse_block), "expected a closing brace");
+    // the failure is further than the message
+    assert_eq!(message("{(a=", parse_block), "expected `)` or `a`..=`z`, found `=`");
+}
+
+#[test]
+fn renamed() {
+    let error = state("(=", parse_expression_hard).unwrap_err().renamed_rules(|rule| format!("{:?}", rule));
+    assert_eq!(error.variant.message(), "expected expression, found `=`");
+}
+
+fn message(input: &str, f: fn(Input) -> Output) -> String {
+    state(input, f).unwrap_err().variant.message().to_string()
+}
+
+fn builtin_text<'i>(state: Input<'i>, text: &'static str, case: bool) -> Output<'i> {
+    state.rule(Rule::IgnoreText, |s| s.match_string(text, case))
+}
+
+/// ```ygg
+/// class Block {
+///     '{' ExpressionHard* @error("expected a closing brace", '}')
+/// }
+/// ```
+fn parse_block(state: Input) -> Output {
+    state.rule(Rule::block, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| builtin_text(s, "{", false))
+                .and_then(|s| s.repeat(0..u32::MAX, parse_expression_hard))
+                .and_then(|s| s.error("expected a closing brace", |s| builtin_text(s, "}", false)))
+        })
+    })
+}
+
+/// ```ygg
+/// @label("expression")
+/// union ExpressionHard {
+///     | '(' ExpressionHard ')'
+///     | Identifier
+///     | Number
+/// }
+/// ```
+fn parse_expression_hard(state: Input) -> Output {
+    state.label("expression", |s| {
+        s.rule(Rule::expression_hard, |s| {
+            s.sequence(|s| {
+                Ok(s)
+                    .and_then(|s| builtin_text(s, "(", false))
+                    .and_then(|s| parse_expression_hard(s))
+                    .and_then(|s| builtin_text(s, ")", false))
+            })
+            .or_else(|s| parse_identifier(s))
+            .or_else(|s| parse_number(s))
+        })
+    })
+}
+
+/// ```ygg
+/// class Identifier {
+///     [a-z]+
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_identifier(state: Input) -> Output {
+    state.rule(Rule::identifier, |s| s.repeat(1..u32::MAX, |s| s.match_range('a'..'z')))
+}
+
+/// ```ygg
+/// class Number {
+///     [0-9]+
+/// }
+/// ```
+fn parse_number(state: Input) -> Output {
+    state.rule(Rule::number, |s| s.repeat(1..u32::MAX, |s| s.match_range('0'..'9')))
+}
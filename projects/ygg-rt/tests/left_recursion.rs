@@ -0,0 +1,90 @@
+use yggdrasil_rt::{state, Either, State, TokenPair, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    expr,
+    term,
+    a,
+    b,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn direct() {
+    assert_eq!(parse("1", parse_expr), "(expr (term 1))");
+    assert_eq!(parse("1+2", parse_expr), "(expr (expr (term 1)) (term 2))");
+    assert_eq!(parse("1+2+3", parse_expr), "(expr (expr (expr (term 1)) (term 2)) (term 3))");
+    assert!(state("+1", parse_expr).is_err());
+}
+
+#[test]
+fn indirect() {
+    assert_eq!(parse("a", parse_a), "(a)");
+    assert_eq!(parse("ayx", parse_a), "(a (b (a)))");
+    assert_eq!(parse("bxyx", parse_a), "(a (b (a (b))))");
+    assert_eq!(parse("ayxyx", parse_a), "(a (b (a (b (a)))))");
+    assert_eq!(parse("ay", parse_b), "(b (a))");
+}
+
+fn parse(input: &str, f: fn(Input) -> Output) -> String {
+    let mut tree = state(input, f).unwrap();
+    let pair = tree.next().unwrap();
+    assert_eq!(pair.as_str(), input);
+    show(pair)
+}
+
+fn show(pair: TokenPair<Rule>) -> String {
+    let mut out = format!("({:?}", pair.get_rule());
+    let mut leaf = true;
+    for inner in pair.clone().into_inner() {
+        leaf = false;
+        out.push(' ');
+        out.push_str(&show(inner));
+    }
+    if leaf && pair.get_rule() == Rule::term {
+        out.push(' ');
+        out.push_str(pair.as_str());
+    }
+    out.push(')');
+    out
+}
+
+/// ```ygg
+/// class Expr {
+///     Expr '+' Term | Term
+/// }
+/// ```
+fn parse_expr(state: Input) -> Output {
+    state.rule_left(Rule::expr, |s| {
+        s.sequence(|s| parse_expr(s).and_then(|s| s.match_string("+", false)).and_then(|s| parse_term(s)))
+            .or_else(|s| parse_term(s))
+    })
+}
+
+fn parse_term(state: Input) -> Output {
+    state.rule(Rule::term, |s| s.match_range('0'..'9'))
+}
+
+/// ```ygg
+/// class A {
+///     B 'x' | 'a'
+/// }
+/// class B {
+///     A 'y' | 'b'
+/// }
+/// ```
+fn parse_a(state: Input) -> Output {
+    state.rule_left(Rule::a, |s| {
+        s.sequence(|s| parse_b(s).and_then(|s| s.match_string("x", false))).or_else(|s| s.match_string("a", false))
+    })
+}
+
+fn parse_b(state: Input) -> Output {
+    state.rule_left(Rule::b, |s| {
+        s.sequence(|s| parse_a(s).and_then(|s| s.match_string("y", false))).or_else(|s| s.match_string("b", false))
+    })
+}
@@ -0,0 +1,94 @@
+use yggdrasil_rt::{state, Either, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    comment,
+    digit,
+    version,
+    never,
+    IgnoreText,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn range() {
+    assert_eq!(matched("7", parse_digit), Some("7".to_string()));
+    assert_eq!(matched("9", parse_digit), Some("9".to_string()));
+    assert_eq!(matched("a", parse_digit), None);
+}
+
+#[test]
+fn rest_of_line() {
+    assert_eq!(matched("# note\nnext", parse_comment), Some("# note".to_string()));
+    assert_eq!(matched("# note\r\nnext", parse_comment), Some("# note".to_string()));
+    assert_eq!(matched("# eof", parse_comment), Some("# eof".to_string()));
+}
+
+#[test]
+fn integer() {
+    assert_eq!(matched("v2", parse_version), Some("v2".to_string()));
+    assert_eq!(matched("v3", parse_version), None);
+}
+
+#[test]
+fn boolean() {
+    assert_eq!(matched("", parse_never), None);
+}
+
+fn matched(input: &str, f: fn(Input) -> Output) -> Option<String> {
+    let tree = state(input, f).ok()?;
+    tree.into_iter().next().map(|pair| pair.as_str().to_string())
+}
+
+fn builtin_text<'i>(state: Input<'i>, text: &'static str, case: bool) -> Output<'i> {
+    state.rule(Rule::IgnoreText, |s| s.match_string(text, case))
+}
+
+fn builtin_rest_of_line(state: Input) -> Output {
+    state.rule(Rule::IgnoreText, |s| s.skip_until(&["\n", "\r"]))
+}
+
+/// ```ygg
+/// class Digit {
+///     [0-9]
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_digit(state: Input) -> Output {
+    state.rule(Rule::digit, |s| s.match_range('0'..'9'))
+}
+
+/// ```ygg
+/// class Comment {
+///     '#' ROL
+/// }
+/// ```
+fn parse_comment(state: Input) -> Output {
+    state.rule(Rule::comment, |s| {
+        s.sequence(|s| Ok(s).and_then(|s| builtin_text(s, "#", false)).and_then(|s| builtin_rest_of_line(s)))
+    })
+}
+
+/// ```ygg
+/// class Version {
+///     'v' 2
+/// }
+/// ```
+fn parse_version(state: Input) -> Output {
+    state.rule(Rule::version, |s| {
+        s.sequence(|s| Ok(s).and_then(|s| builtin_text(s, "v", false)).and_then(|s| builtin_text(s, "2", false)))
+    })
+}
+
+/// ```ygg
+/// class Never {
+///     false
+/// }
+/// ```
+fn parse_never(state: Input) -> Output {
+    state.rule(Rule::never, Err)
+}
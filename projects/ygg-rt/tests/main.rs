@@ -6,7 +6,21 @@ fn ready() {
     println!("it, works!")
 }
 
+mod climb;
+mod compiled;
 mod concat;
+mod cut;
+mod expected;
+mod highlight;
+mod label;
+mod left_recursion;
+mod lowering;
+mod memo;
+mod recover;
+#[cfg(feature = "std")]
+mod render;
+mod reparse;
 mod suffix;
+mod trie;
 
 mod stack;
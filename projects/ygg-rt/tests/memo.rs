@@ -0,0 +1,92 @@
+use std::cell::Cell;
+use yggdrasil_rt::{state, Either, State, TokenPair, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    statement,
+    group,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+thread_local! {
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[test]
+fn test() {
+    assert_eq!(memo("((a))y", false), ("(statement (group (group (group))))".to_string(), 14));
+    assert_eq!(memo("((a))y", true), ("(statement (group (group (group))))".to_string(), 3));
+    assert_eq!(
+        memo("((((((a))))))y", true),
+        ("(statement (group (group (group (group (group (group (group))))))))".to_string(), 7)
+    );
+}
+
+#[test]
+fn failure() {
+    CALLS.with(|c| c.set(0));
+    assert!(state("((a)", |s| parse_statement(s, true)).is_err());
+    assert_eq!(CALLS.with(|c| c.get()), 3);
+}
+
+fn memo(input: &str, memo: bool) -> (String, usize) {
+    CALLS.with(|c| c.set(0));
+    let mut tree = state(input, |s| parse_statement(s, memo)).unwrap();
+    (show(tree.next().unwrap()), CALLS.with(|c| c.get()))
+}
+
+fn show(pair: TokenPair<Rule>) -> String {
+    let mut out = format!("({:?}", pair.get_rule());
+    for inner in pair.into_inner() {
+        out.push(' ');
+        out.push_str(&show(inner));
+    }
+    out.push(')');
+    out
+}
+
+/// ```ygg
+/// class Statement {
+///     Group 'x' | Group 'y'
+/// }
+/// ```
+fn parse_statement(state: Input, memo: bool) -> Output {
+    state.rule(Rule::statement, |s| {
+        s.sequence(|s| parse_group(s, memo).and_then(|s| s.match_string("x", false)))
+            .or_else(|s| s.sequence(|s| parse_group(s, memo).and_then(|s| s.match_string("y", false))))
+    })
+}
+
+/// ```ygg
+/// #memo()
+/// class Group {
+///     '(' Group ')' 'x'? | '(' Group ')' | 'a'
+/// }
+/// ```
+fn parse_group(state: Input, memo: bool) -> Output {
+    match memo {
+        true => state.rule_memo(Rule::group, |s| group_body(s, memo)),
+        false => state.rule(Rule::group, |s| group_body(s, memo)),
+    }
+}
+
+fn group_body(state: Input, memo: bool) -> Output {
+    CALLS.with(|c| c.set(c.get() + 1));
+    state
+        .sequence(|s| {
+            s.match_string("(", false)
+                .and_then(|s| parse_group(s, memo))
+                .and_then(|s| s.match_string(")", false))
+                .and_then(|s| s.match_string("x", false))
+        })
+        .or_else(|s| {
+            s.sequence(|s| {
+                s.match_string("(", false).and_then(|s| parse_group(s, memo)).and_then(|s| s.match_string(")", false))
+            })
+        })
+        .or_else(|s| s.match_string("a", false))
+}
@@ -0,0 +1,81 @@
+use yggdrasil_rt::{state, state_recover, Either, State, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    program,
+    statement,
+    error,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn terminator() {
+    check("a=1;b=2;", false, &["a=1;", "b=2;"], 0);
+    check("a=1;b=;c=3;", false, &["a=1;", "error: b=;", "c=3;"], 1);
+    check("a=;b=;c", false, &["error: a=;", "error: b=;", "error: c"], 3);
+    check("", false, &[], 0);
+}
+
+#[test]
+fn next_statement() {
+    check("a=1;!!b=2;", true, &["a=1;", "error: !!", "b=2;"], 1);
+    check("a==1;b=2;", true, &["error: a==1;", "b=2;"], 1);
+}
+
+#[test]
+fn normal_mode() {
+    assert!(state("a=1;b=;c=3;", |s| parse_program(s, false)).is_err());
+}
+
+fn check(input: &str, lookahead: bool, nodes: &[&str], errors: usize) {
+    let (tree, diagnostics) = state_recover(input, |s| parse_program(s, lookahead));
+    let program = tree.into_iter().next().unwrap();
+    let mut out = vec![];
+    for pair in program.into_inner() {
+        match pair.get_rule() {
+            Rule::error => out.push(format!("error: {}", pair.as_str())),
+            _ => out.push(pair.as_str().to_string()),
+        }
+    }
+    assert_eq!(out, nodes);
+    assert_eq!(diagnostics.len(), errors);
+}
+
+/// ```ygg
+/// class Program {
+///     Statement* EOI
+/// }
+/// ```
+fn parse_program(state: Input, lookahead: bool) -> Output {
+    state.rule(Rule::program, |s| {
+        s.sequence(|s| s.repeat(0..u32::MAX, |s| recover_statement(s, lookahead)).and_then(|s| s.end_of_input()))
+    })
+}
+
+/// ```ygg
+/// #recover(';')
+/// class Statement {
+///     [a-z] '=' [0-9] ';'
+/// }
+/// ```
+fn recover_statement(state: Input, lookahead: bool) -> Output {
+    state.recover(Rule::error, parse_statement, |s| match lookahead {
+        true => s.lookahead(true, parse_statement),
+        false => s.match_string(";", false),
+    })
+}
+
+fn parse_statement(state: Input) -> Output {
+    state.rule(Rule::statement, |s| {
+        s.sequence(|s| {
+            s.match_range('a'..'z')
+                .and_then(|s| s.match_string("=", false))
+                .and_then(|s| s.match_range('0'..'9'))
+                .and_then(|s| s.match_string(";", false))
+        })
+    })
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "std")]
+
+use yggdrasil_rt::{state, Either, ErrorRenderer, State, TextSpan, YggdrasilError, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    program,
+    block,
+    identifier,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+#[test]
+fn snippet() {
+    let input = "a;\nb 1;\n";
+    let error = state(input, parse_program).unwrap_err().with_path("main.ygg");
+    assert_eq!(
+        render(input, &error),
+        "\
+error: expected `;` or `a`..=`z`, found ` `
+ --> main.ygg:2:2
+  |
+2 | b 1;
+  |  ^ expected `;` or `a`..=`z`
+"
+    );
+}
+
+#[test]
+fn end_of_input() {
+    let input = "a;\nb";
+    let error = state(input, parse_program).unwrap_err();
+    assert_eq!(
+        render(input, &error),
+        "\
+error: expected `;` or `a`..=`z`, found end of input
+ --> 2:2
+  |
+2 | b
+  |  ^ expected `;` or `a`..=`z`
+"
+    );
+}
+
+#[test]
+fn spans() {
+    let input = "x {\n  a;\n}";
+    let error = YggdrasilError::invalid_node(Rule::block, TextSpan::new(input, 2, 10).unwrap());
+    assert_eq!(
+        render(input, &error),
+        "\
+error: invalid node, expected node block
+ --> 1:3
+  |
+1 |   x {
+  |  ___^
+2 | |   a;
+3 | | }
+  | |_^ expected block
+"
+    );
+    let error = YggdrasilError::invalid_node(Rule::identifier, TextSpan::new(input, 6, 7).unwrap());
+    assert_eq!(
+        render(input, &error),
+        "\
+error: invalid node, expected node identifier
+ --> 2:3
+  |
+2 |   a;
+  |   ^ expected identifier
+"
+    );
+}
+
+#[test]
+fn long_spans() {
+    let input = "{\n1\n2\n3\n4\n5\n6\n7\n8\n9\n}";
+    let error = YggdrasilError::invalid_node(Rule::block, TextSpan::new(input, 0, input.len()).unwrap());
+    assert_eq!(
+        render(input, &error),
+        "\
+error: invalid node, expected node block
+  --> 1:1
+   |
+ 1 |   {
+   |  _^
+ 2 | | 1
+.. |
+10 | | 9
+11 | | }
+   | |_^ expected block
+"
+    );
+}
+
+#[test]
+fn colors() {
+    let input = "1";
+    let error = state(input, parse_program).unwrap_err();
+    let text = ErrorRenderer::new(input).with_color(true).render(&error);
+    assert!(text.starts_with("\x1b[1;31merror\x1b[0m: "));
+    assert_eq!(strip_ansi(&text), render(input, &error));
+}
+
+fn render(input: &str, error: &YggdrasilError<Rule>) -> String {
+    ErrorRenderer::new(input).with_color(false).render(error)
+}
+
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::new();
+    let mut escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => escape = true,
+            'm' if escape => escape = false,
+            _ if escape => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// ```ygg
+/// class Program {
+///     (Identifier ';')* EOI
+/// }
+/// ```
+fn parse_program(state: Input) -> Output {
+    state.rule(Rule::program, |s| {
+        s.sequence(|s| {
+            Ok(s)
+                .and_then(|s| {
+                    s.repeat(0..u32::MAX, |s| {
+                        s.sequence(|s| {
+                            Ok(s)
+                                .and_then(|s| parse_identifier(s))
+                                .and_then(|s| s.match_string(";", false))
+                                .and_then(|s| s.match_string("\n", false).or_else(Ok))
+                        })
+                    })
+                })
+                .and_then(|s| s.end_of_input())
+        })
+    })
+}
+
+/// ```ygg
+/// class Identifier {
+///     [a-z]+
+/// }
+/// ```
+#[allow(clippy::almost_complete_range)]
+fn parse_identifier(state: Input) -> Output {
+    state.rule(Rule::identifier, |s| s.repeat(1..u32::MAX, |s| s.match_range('a'..'z')))
+}
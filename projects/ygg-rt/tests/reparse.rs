@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use yggdrasil_rt::{state, state_reparse, Either, State, TokenPair, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    program,
+    block,
+    statement,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+thread_local! {
+    static CALLS: RefCell<Vec<Rule>> = const { RefCell::new(vec![]) };
+}
+
+#[test]
+fn innermost() {
+    let calls = check("a=1;{b=2;c=3;}", 7..8, "42");
+    assert_eq!(calls, [Rule::statement]);
+}
+
+#[test]
+fn shift_after() {
+    let calls = check("a=1;b=2;{c=3;}", 2..3, "123");
+    assert_eq!(calls, [Rule::statement]);
+    let calls = check("a=12345;b=2;{c=3;}", 2..6, "");
+    assert_eq!(calls, [Rule::statement]);
+}
+
+#[test]
+fn grow_to_parent() {
+    // the block is split into two
+    let calls = check("a=1;{b=2;c=3;}", 9..9, "}{");
+    assert_eq!(calls[0], Rule::block);
+    assert!(calls.contains(&Rule::program));
+    // the statement doesn't end at the old place
+    let calls = check("a=1;{b=2;c=3;}", 7..8, "2;d=4");
+    assert_eq!(calls[..2], [Rule::statement, Rule::block]);
+    assert!(!calls.contains(&Rule::program));
+}
+
+#[test]
+fn full_reparse() {
+    let calls = check("a=1;b=2;", 0..0, "x=0;");
+    assert_eq!(calls[0], Rule::program);
+}
+
+#[test]
+fn broken() {
+    let old = state("a=1;{b=2;}", parse_program).unwrap();
+    assert!(state_reparse(&old, 7..8, "x", "a=1;{b=x;}", Rule::program, parse_rule).is_err());
+}
+
+/// Reparse and compare with the full parse, returns the rules parsed again
+fn check(input: &str, edit: std::ops::Range<usize>, text: &str) -> Vec<Rule> {
+    let mut new = input.to_string();
+    new.replace_range(edit.clone(), text);
+    let old = state(input, parse_program).unwrap();
+    CALLS.with(|c| c.borrow_mut().clear());
+    let tree = state_reparse(&old, edit, text, &new, Rule::program, parse_rule).unwrap();
+    let calls = CALLS.with(|c| c.take());
+    let expect = state(&new, parse_program).unwrap();
+    assert_eq!(tree.map(show).collect::<Vec<_>>(), expect.map(show).collect::<Vec<_>>());
+    calls
+}
+
+fn show(pair: TokenPair<Rule>) -> String {
+    let mut out = format!("({:?}", pair.get_rule());
+    if let Some(tag) = pair.get_tag() {
+        out.push_str(&format!(" #{tag}"));
+    }
+    out.push_str(&format!(" {:?} {:?}", pair.get_span().start()..pair.get_span().end(), pair.as_str()));
+    for inner in pair.into_inner() {
+        out.push(' ');
+        out.push_str(&show(inner));
+    }
+    out.push(')');
+    out
+}
+
+fn parse_rule(state: Input, rule: Rule) -> Output {
+    match rule {
+        Rule::program => parse_program(state),
+        Rule::block => parse_block(state),
+        Rule::statement => parse_statement(state),
+    }
+}
+
+/// ```ygg
+/// class Program {
+///     (Block | Statement)* EOI
+/// }
+/// ```
+fn parse_program(state: Input) -> Output {
+    state.rule(Rule::program, |s| {
+        CALLS.with(|c| c.borrow_mut().push(Rule::program));
+        s.sequence(|s| s.repeat(0..u32::MAX, parse_item).and_then(|s| s.end_of_input()))
+    })
+}
+
+fn parse_item(state: Input) -> Output {
+    parse_block(state).or_else(parse_statement)
+}
+
+/// ```ygg
+/// class Block {
+///     '{' body:(Block | Statement)* '}'
+/// }
+/// ```
+fn parse_block(state: Input) -> Output {
+    state.rule(Rule::block, |s| {
+        CALLS.with(|c| c.borrow_mut().push(Rule::block));
+        s.sequence(|s| {
+            s.match_string("{", false)
+                .and_then(|s| s.repeat(0..u32::MAX, |s| parse_item(s).and_then(|s| s.tag_node("body"))))
+                .and_then(|s| s.match_string("}", false))
+        })
+    })
+}
+
+/// ```ygg
+/// class Statement {
+///     [a-z] '=' [0-9]+ ';'
+/// }
+/// ```
+fn parse_statement(state: Input) -> Output {
+    state.rule(Rule::statement, |s| {
+        CALLS.with(|c| c.borrow_mut().push(Rule::statement));
+        s.sequence(|s| {
+            s.match_range('a'..'z')
+                .and_then(|s| s.match_string("=", false))
+                .and_then(|s| s.repeat(1..u32::MAX, |s| s.match_range('0'..'9')))
+                .and_then(|s| s.match_string(";", false))
+        })
+    })
+}
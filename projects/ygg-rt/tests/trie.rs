@@ -0,0 +1,55 @@
+use yggdrasil_rt::{state, Either, State, TextTrie, YggdrasilRule};
+
+impl YggdrasilRule for Rule {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Rule {
+    keyword,
+}
+
+type Input<'i> = Box<State<'i, Rule>>;
+type Output<'i> = Either<Box<State<'i, Rule>>>;
+
+const KEYWORDS: &[&str] = &["for", "in", "if", "foreach", "i", ""];
+
+#[test]
+fn same_as_choice() {
+    for input in ["for", "foreach", "in", "if", "i", "x", "", "fo", "forx"] {
+        assert_eq!(keyword(input, true), keyword(input, false), "{input:?}");
+    }
+    assert_eq!(keyword("foreach", true), Some("for".to_string()));
+    assert_eq!(keyword("x", true), Some("".to_string()));
+}
+
+#[test]
+fn insensitive() {
+    let trie = TextTrie::new(&["If", "in"], true);
+    assert_eq!(trie.find("IN x"), Some(2));
+    assert_eq!(trie.find("iF"), Some(2));
+    assert_eq!(trie.find("i"), None);
+    let trie = TextTrie::new(&["If", "in"], false);
+    assert_eq!(trie.find("if"), None);
+}
+
+fn keyword(input: &str, trie: bool) -> Option<String> {
+    let tree = state(input, |s| parse_keyword(s, trie)).ok()?;
+    tree.into_iter().next().map(|pair| pair.as_str().to_string())
+}
+
+/// ```ygg
+/// class Keyword {
+///     'for' | 'in' | 'if' | 'foreach' | 'i' | ''
+/// }
+/// ```
+fn parse_keyword(state: Input, trie: bool) -> Output {
+    state.rule(Rule::keyword, |s| match trie {
+        true => s.match_trie(&TextTrie::new(KEYWORDS, false)),
+        false => {
+            let mut s = Err(s);
+            for text in KEYWORDS {
+                s = s.or_else(|s| s.match_string(text, false));
+            }
+            s
+        }
+    })
+}